@@ -2,19 +2,46 @@ use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 
+use crate::config::{CookieSource, HumanDuration};
+use crate::file_parser::{language::Language, problem_ref::ProblemRef};
+use crate::handlers::problems::ProblemFilter;
+use crate::output::OutputFormat;
+
 #[derive(Parser)]
 #[command(version, arg_required_else_help = true)]
 #[command(propagate_version = true)]
 pub struct Cli {
+    /// Base URL of the LeetCode instance, e.g. https://leetcode.cn
+    #[arg(long, global = true, env = "LC_BASE_URL")]
+    pub base_url: Option<String>,
+    /// Output format, json and ndjson print machine readable results
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// How long to wait for a run or submission result, e.g. 30s or 2m
+    #[arg(long, global = true)]
+    pub timeout: Option<HumanDuration>,
+    /// Where to read the session cookie from, e.g. firefox or chromium:<PROFILE>,
+    /// overrides the cookie_source config
+    #[arg(long, global = true, value_name = "SOURCE")]
+    pub cookie_from: Option<CookieSource>,
+    /// Profile to use instead of the one chosen with `profile use`
+    #[arg(long, global = true, env = "LC_PROFILE")]
+    pub profile: Option<String>,
+    /// Only use cached question data, without reaching LeetCode
+    #[arg(long, global = true)]
+    pub offline: bool,
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Authenticate with LeetCode
+    /// Authenticate with LeetCode, shows the status unless told otherwise
     #[command(visible_alias = "-a")]
-    Auth,
+    Auth {
+        #[command(subcommand)]
+        command: Option<AuthCommands>,
+    },
     /// Executes code with testcases
     #[command(visible_alias = "-r")]
     Run {
@@ -24,6 +51,12 @@ pub enum Commands {
         #[arg(short, long)]
         /// Testcases to run
         testcase_file: Option<String>,
+        #[arg(short, long)]
+        /// Compile and run the testcases locally instead of on LeetCode
+        local: bool,
+        #[arg(short, long, requires = "local", requires = "testcase_file")]
+        /// Expected answers of the testcase file for the local run, one per line
+        expected_file: Option<PathBuf>,
     },
     /// Submits code to LeetCode
     #[command(visible_alias = "-fs")]
@@ -41,14 +74,25 @@ pub enum Commands {
         /// Testcases to run
         testcase_file: Option<String>,
     },
+    /// Fetches the result of a run or submission by the id printed when it started
+    Check {
+        /// Run id like runcode_..., or numeric submission id
+        id: String,
+    },
     /// Save a question as HTML
     #[command(visible_alias = "-q")]
     Question {
-        /// Question name
-        question_name: String,
+        /// Question slug, url, number or title, titles may be misspelled
+        question_name: ProblemRef,
         /// Flag to not save the boilerplate code
         #[arg(short, long)]
         no_code_save: bool,
+        /// Language of the boilerplate code
+        #[arg(short, long)]
+        lang: Option<Language>,
+        /// Path of the boilerplate code, may use {slug}, {lang} and {ext}
+        #[arg(short, long)]
+        output: Option<String>,
     },
     /// Save today's daily challenge as HTML
     #[command(visible_alias = "-d")]
@@ -56,6 +100,60 @@ pub enum Commands {
         /// Flag to not save the boilerplate code
         #[arg(short, long)]
         no_code_save: bool,
+        /// Language of the boilerplate code
+        #[arg(short, long)]
+        lang: Option<Language>,
+        /// Path of the boilerplate code, may use {slug}, {lang} and {ext}
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Lists the problems matching the filters
+    #[command(visible_alias = "-ls")]
+    List {
+        #[command(flatten)]
+        filter: ProblemFilter,
+        /// Only print the slugs, one per line
+        #[arg(long)]
+        slugs: bool,
+    },
+    /// Save a random problem matching the filters as HTML, like `question`
+    Random {
+        #[command(flatten)]
+        filter: ProblemFilter,
+        /// Only problems which aren't solved yet, needs a session
+        #[arg(short, long, conflicts_with = "status")]
+        unsolved: bool,
+        /// Seed of the pick, the same seed picks the same problem of the same list
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Flag to not save the boilerplate code
+        #[arg(short, long)]
+        no_code_save: bool,
+        /// Language of the boilerplate code
+        #[arg(short, long)]
+        lang: Option<Language>,
+        /// Path of the boilerplate code, may use {slug}, {lang} and {ext}
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Save many questions at once, with their boilerplate code
+    #[command(visible_alias = "-dl")]
+    Download {
+        /// Question slugs, urls, numbers or titles
+        #[arg(required_unless_present = "from_file")]
+        questions: Vec<ProblemRef>,
+        /// File with a question slug, url, number or title per line
+        #[arg(short, long)]
+        from_file: Option<PathBuf>,
+        /// Language of the boilerplate code
+        #[arg(short, long)]
+        lang: Option<Language>,
+        /// Path of the boilerplate code, may use {slug}, {lang} and {ext}
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Number of questions fetched at once
+        #[arg(short, long, default_value_t = 4)]
+        jobs: usize,
     },
     /// Packs the solution with the question into a directory
     #[command(visible_alias = "-p")]
@@ -64,4 +162,175 @@ pub enum Commands {
         /// File to pack
         file: Option<PathBuf>,
     },
+    /// Manage the saved testcases of a question
+    #[command(visible_alias = "-tc")]
+    Testcase {
+        /// Question title slug, taken from the code file when omitted
+        #[arg(short, long, global = true)]
+        question: Option<String>,
+        /// Code file of the question
+        #[arg(short, long, global = true, conflicts_with = "question")]
+        file: Option<PathBuf>,
+        #[command(subcommand)]
+        command: TestcaseCommands,
+    },
+    /// Manage the cache of question data
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    /// Manage the profiles of several accounts
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+    /// Manage the persistent configuration
+    #[command(visible_alias = "-c")]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+impl Commands {
+    /// Whether the command needs a logged in session, the others only use
+    /// public question data and local files
+    pub fn needs_auth(&self) -> bool {
+        match self {
+            Commands::Auth { command } => !matches!(
+                command,
+                Some(AuthCommands::Login { .. }) | Some(AuthCommands::Logout)
+            ),
+            Commands::Run { local, .. } => !local,
+            Commands::List { filter, .. } => filter.status.is_some(),
+            Commands::Random {
+                filter, unsolved, ..
+            } => filter.status.is_some() || *unsolved,
+            Commands::FastSubmit { .. } | Commands::Submit { .. } | Commands::Check { .. } => true,
+            Commands::Question { .. }
+            | Commands::DailyChallenge { .. }
+            | Commands::Download { .. }
+            | Commands::Pack { .. }
+            | Commands::Testcase { .. }
+            | Commands::Cache { .. }
+            | Commands::Profile { .. }
+            | Commands::Config { .. } => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AuthCommands {
+    /// Checks a cookie and saves it, read from stdin unless --cookie-from is given
+    Login {
+        /// Encrypt the saved cookie with a passphrase, taken from LC_PASSPHRASE when set
+        #[arg(short, long)]
+        encrypt: bool,
+    },
+    /// Removes the saved cookie
+    Logout,
+    /// Shows the user, where the cookie comes from and when it expires
+    Status,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Prints the value of a configuration key
+    Get {
+        /// Configuration key
+        key: String,
+    },
+    /// Sets a configuration key, an empty value unsets it
+    Set {
+        /// Configuration key
+        key: String,
+        /// Value of the key
+        value: String,
+        /// Write to the per-repository config instead of the global one
+        #[arg(short, long)]
+        local: bool,
+    },
+    /// Lists the effective configuration
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    /// Adds a profile, or changes the given settings of an existing one
+    Add {
+        /// Name of the profile
+        name: String,
+        /// Where the cookie of the profile is read from, its own `auth login` cookie by default
+        #[arg(short, long)]
+        cookie_source: Option<CookieSource>,
+        /// Preferred language of the profile
+        #[arg(short, long)]
+        lang: Option<Language>,
+        /// Directory where questions are saved and solutions packed
+        #[arg(short, long)]
+        workspace: Option<PathBuf>,
+    },
+    /// Lists the profiles, the active one is marked with *
+    List,
+    /// Removes a profile and its saved cookie
+    Remove {
+        /// Name of the profile
+        name: String,
+    },
+    /// Makes a profile the default one, none when no name is given
+    Use {
+        /// Name of the profile
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Removes the cached question data
+    Clear {
+        /// Only remove entries older than the cache_ttl
+        #[arg(short, long)]
+        expired: bool,
+    },
+    /// Shows the number and size of the cached entries
+    Stats,
+}
+
+#[derive(Subcommand)]
+pub enum TestcaseCommands {
+    /// Saves a testcase, read from stdin when no input is given
+    Add {
+        /// Input lines of the testcase, one per argument
+        input: Vec<String>,
+        /// Expected answer, captured from LeetCode on the next run otherwise
+        #[arg(short, long)]
+        expected: Option<String>,
+        /// Tags of the testcase
+        #[arg(short, long)]
+        tag: Vec<String>,
+        /// Save the testcase disabled
+        #[arg(short, long)]
+        disabled: bool,
+    },
+    /// Lists the saved testcases
+    List {
+        /// Only list testcases with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+    /// Removes testcases
+    Remove {
+        /// Ids of the testcases, as shown by list
+        #[arg(required = true)]
+        ids: Vec<usize>,
+    },
+    /// Enables testcases, or disables them with --disable
+    Enable {
+        /// Ids of the testcases, as shown by list
+        #[arg(required = true)]
+        ids: Vec<usize>,
+        /// Disable the testcases instead
+        #[arg(short, long)]
+        disable: bool,
+    },
 }
@@ -0,0 +1,207 @@
+//! On-disk cache of LeetCode responses
+//!
+//! Responses are kept in `<cache dir>/leetcode-runner/<host>/<query>/<key>.json`
+//! and refetched once older than the TTL. Offline, any cached response is
+//! served regardless of its age, as is a stale one when LeetCode can't be reached.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::output::OfflineError;
+
+use colored::Colorize;
+use eyre::{bail, Result};
+use serde::Serialize;
+
+const CACHE_DIR: &str = "leetcode-runner";
+pub(crate) const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    /// Directory of the LeetCode instance
+    dir: PathBuf,
+    ttl: Duration,
+    offline: bool,
+}
+
+/// Outcome of looking up a response
+pub(crate) enum Lookup {
+    /// Fresh, or any cached response when offline
+    Hit(String),
+    /// Older than the TTL, still good when LeetCode can't be reached
+    Stale(String),
+    Miss,
+}
+
+impl ResponseCache {
+    /// Cache of the instance at `base_url`, `None` without a cache directory
+    pub fn new(base_url: &str, ttl: Duration, offline: bool) -> Option<Self> {
+        let url = reqwest::Url::parse(base_url).ok()?;
+        let host = match url.port() {
+            Some(port) => format!("{}_{}", url.host_str()?, port),
+            None => url.host_str()?.to_string(),
+        };
+        Some(Self {
+            dir: Self::root()?.join(sanitize(&host)),
+            ttl,
+            offline,
+        })
+    }
+
+    /// Directory shared by the caches of all instances
+    pub fn root() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join(CACHE_DIR))
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    fn path(&self, query: &str, key: &str) -> PathBuf {
+        self.dir
+            .join(sanitize(query))
+            .join(format!("{}.json", sanitize(key)))
+    }
+
+    /// Looks up the response to `query` for `key`, offline a miss is an error
+    pub(crate) fn lookup(&self, query: &str, key: &str) -> Result<Lookup> {
+        let path = self.path(query, key);
+        let Ok(body) = std::fs::read_to_string(&path) else {
+            if self.offline {
+                bail!(OfflineError(format!(
+                    "{} is not cached yet, fetch it once without --offline",
+                    key
+                )));
+            }
+            return Ok(Lookup::Miss);
+        };
+        if self.offline || age(&path).is_some_and(|age| age < self.ttl) {
+            Ok(Lookup::Hit(body))
+        } else {
+            Ok(Lookup::Stale(body))
+        }
+    }
+
+    /// Saves a response, failing to write only loses the cache entry
+    pub(crate) fn store(&self, query: &str, key: &str, body: &str) {
+        let path = self.path(query, key);
+        let tmp = path.with_extension("tmp");
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&tmp, body))
+            .and_then(|_| std::fs::rename(&tmp, &path));
+        if let Err(err) = written {
+            eprintln!("Failed to cache {} : {}", path.display(), err);
+        }
+    }
+}
+
+/// Age of a cache entry, `None` when the clock went backwards
+fn age(path: &Path) -> Option<Duration> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    SystemTime::now().duration_since(modified).ok()
+}
+
+/// Keeps cache paths inside their directory whatever the slug or host
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-_.".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.chars().all(|c| c == '.') {
+        name.replace('.', "_")
+    } else {
+        name
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CacheStats {
+    pub location: PathBuf,
+    pub entries: usize,
+    /// Entries older than the TTL
+    pub expired: usize,
+    pub bytes: u64,
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:10}: {}\n{:10}: {} ({} expired)\n{:10}: {:.1} KiB",
+            "Location",
+            self.location.display().to_string().cyan(),
+            "Entries",
+            self.entries,
+            self.expired,
+            "Size",
+            self.bytes as f64 / 1024.0
+        )
+    }
+}
+
+/// Counts the cached responses of every instance
+pub fn stats(ttl: Duration) -> Result<CacheStats> {
+    let Some(root) = ResponseCache::root() else {
+        bail!("No cache directory on this platform");
+    };
+    let mut stats = CacheStats {
+        location: root.clone(),
+        ..Default::default()
+    };
+    for entry in entries(&root)? {
+        stats.entries += 1;
+        stats.bytes += std::fs::metadata(&entry).map_or(0, |meta| meta.len());
+        if age(&entry).is_none_or(|age| age >= ttl) {
+            stats.expired += 1;
+        }
+    }
+    Ok(stats)
+}
+
+/// Removes cached responses, only those older than `ttl` when given.
+/// Returns the number of removed entries
+pub fn clear(ttl: Option<Duration>) -> Result<usize> {
+    let Some(root) = ResponseCache::root() else {
+        bail!("No cache directory on this platform");
+    };
+    let mut removed = 0;
+    for entry in entries(&root)? {
+        let expired = match ttl {
+            Some(ttl) => age(&entry).is_none_or(|age| age >= ttl),
+            None => true,
+        };
+        if expired {
+            std::fs::remove_file(&entry)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Files of the cache below `dir`
+fn entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(entries(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
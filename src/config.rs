@@ -0,0 +1,541 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::cache::DEFAULT_CACHE_TTL;
+use crate::cookies::BrowserProfile;
+use crate::credentials;
+use crate::file_parser::language::Language;
+use crate::handlers::leetcode::PollOptions;
+use crate::output::AuthError;
+use crate::LC_COOKIE_ENV_KEY;
+
+use eyre::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+const CONFIG_DIR: &str = "leetcode-runner";
+const CONFIG_FILE: &str = "config.toml";
+/// Per-repository override, looked up in the current directory and its ancestors
+pub(crate) const LOCAL_CONFIG_FILE: &str = ".leetcode-runner.toml";
+
+pub(crate) const DEFAULT_FILENAME: &str = "main.{ext}";
+pub(crate) const DEFAULT_PACK_LAYOUT: &str = "{slug}/main.{ext}";
+
+/// Keys accepted by `config get` and `config set`
+pub(crate) const KEYS: [&str; 9] = [
+    "cookie_source",
+    "base_url",
+    "language",
+    "filename",
+    "html_dir",
+    "pack_layout",
+    "poll_interval",
+    "poll_timeout",
+    "cache_ttl",
+];
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Where the session cookie is read from, e.g. `env:<VAR>` or `stored`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookie_source: Option<CookieSource>,
+    /// LeetCode instance to talk to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Preferred language for boilerplate code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    /// Filename template for boilerplate code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Directory where question HTML is saved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html_dir: Option<PathBuf>,
+    /// Path template of the packed solution, its README is saved alongside
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_layout: Option<String>,
+    /// Delay between the first checks of a run or submission result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<HumanDuration>,
+    /// How long to wait for a run or submission result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_timeout: Option<HumanDuration>,
+    /// How long cached question data is served before being fetched again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<HumanDuration>,
+    /// Profile used unless another one is selected, set by `profile use`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Languages added or extended, by LeetCode's `langSlug`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, LanguageConfig>,
+}
+
+/// Settings of an account, overriding the global ones while it's active
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookie_source: Option<CookieSource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    /// Directory where questions are saved and solutions packed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
+}
+
+/// A language unknown to this release, or more names for a builtin one
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LanguageConfig {
+    /// Extensions of its code files, the first one is used for new files
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+    /// Other names accepted by `--lang` and the `language` key
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Token starting an inline comment, required by new languages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Command compiling the solution for local runs, like the runner toolchains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build: Option<Vec<String>>,
+    /// Command running the solution for local runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run: Option<Vec<String>>,
+}
+
+impl Config {
+    /// Loads the global configuration, overridden by the per-repository one.
+    /// Their languages are defined first since the other keys may name them
+    pub fn load() -> Result<Self> {
+        let files = [Self::global_path(), Self::local_path()]
+            .into_iter()
+            .flatten()
+            .filter(|path| path.exists())
+            .map(|path| {
+                let contents = std::fs::read_to_string(&path)?;
+                let table: toml::Table = toml::from_str(&contents)
+                    .wrap_err_with(|| format!("Failed to parse config file {}", path.display()))?;
+                Ok((path, table))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut languages = BTreeMap::new();
+        for (path, table) in &files {
+            if let Some(defined) = table.get("languages") {
+                let defined: BTreeMap<String, LanguageConfig> = defined
+                    .clone()
+                    .try_into()
+                    .wrap_err_with(|| format!("Failed to parse config file {}", path.display()))?;
+                languages.extend(defined);
+            }
+        }
+        Language::define(&languages)?;
+
+        files
+            .into_iter()
+            .try_fold(Self::default(), |config, (path, table)| {
+                let file: Self = toml::Value::Table(table)
+                    .try_into()
+                    .wrap_err_with(|| format!("Failed to parse config file {}", path.display()))?;
+                Ok(config.merge(file))
+            })
+    }
+
+    /// Reads a single configuration file, a missing file is an empty configuration
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents)
+            .wrap_err_with(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn global_path() -> Option<PathBuf> {
+        Self::dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    /// Directory of the global configuration, also holding the saved cookie
+    pub fn dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(CONFIG_DIR))
+    }
+
+    /// Nearest per-repository configuration file, if any
+    pub fn local_path() -> Option<PathBuf> {
+        let cwd = std::env::current_dir().ok()?;
+        cwd.ancestors()
+            .map(|dir| dir.join(LOCAL_CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Values set in `other` take precedence
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            cookie_source: other.cookie_source.or(self.cookie_source),
+            base_url: other.base_url.or(self.base_url),
+            language: other.language.or(self.language),
+            filename: other.filename.or(self.filename),
+            html_dir: other.html_dir.or(self.html_dir),
+            pack_layout: other.pack_layout.or(self.pack_layout),
+            poll_interval: other.poll_interval.or(self.poll_interval),
+            poll_timeout: other.poll_timeout.or(self.poll_timeout),
+            cache_ttl: other.cache_ttl.or(self.cache_ttl),
+            profile: other.profile.or(self.profile),
+            profiles: self.profiles.into_iter().chain(other.profiles).collect(),
+            languages: self.languages.into_iter().chain(other.languages).collect(),
+        }
+    }
+
+    /// Profile selected with `--profile`, or by `profile use` otherwise
+    pub fn active_profile(&self, selected: Option<&str>) -> Result<Option<(String, Profile)>> {
+        let Some(name) = selected.or(self.profile.as_deref()) else {
+            return Ok(None);
+        };
+        match self.profiles.get(name) {
+            Some(profile) => Ok(Some((name.to_string(), profile.clone()))),
+            None => bail!(
+                "No profile named {}, add it with `profile add {}`",
+                name,
+                name
+            ),
+        }
+    }
+
+    /// Settings of `profile` take precedence, its cookie is the one saved
+    /// by `auth login` unless it has a cookie source
+    pub fn with_profile(self, profile: &Profile) -> Self {
+        Self {
+            cookie_source: Some(
+                profile
+                    .cookie_source
+                    .clone()
+                    .unwrap_or(CookieSource::Stored),
+            ),
+            language: profile.language.or(self.language),
+            ..self
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        if !KEYS.contains(&key) {
+            bail!(
+                "Unknown config key {}, expected one of {}",
+                key,
+                KEYS.join(", ")
+            );
+        }
+        let table = toml::Table::try_from(self)?;
+        Ok(table.get(key).map(|value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or(value.to_string())
+        }))
+    }
+
+    /// Sets `key` to `value`, an empty value unsets the key
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        if !KEYS.contains(&key) {
+            bail!(
+                "Unknown config key {}, expected one of {}",
+                key,
+                KEYS.join(", ")
+            );
+        }
+        let mut table = toml::Table::try_from(&*self)?;
+        if value.is_empty() {
+            table.remove(key);
+        } else {
+            table.insert(key.to_string(), toml::Value::String(value.to_string()));
+        }
+        *self = table
+            .try_into()
+            .wrap_err_with(|| format!("Invalid value for {}", key))?;
+        Ok(())
+    }
+
+    /// Configured cookie source, otherwise `LC_COOKIE` unless it's unset
+    /// and there is a cookie saved by `auth login`
+    pub fn cookie_source(&self) -> CookieSource {
+        match &self.cookie_source {
+            Some(source) => source.clone(),
+            None if std::env::var_os(LC_COOKIE_ENV_KEY).is_none() && credentials::exists() => {
+                CookieSource::Stored
+            }
+            None => CookieSource::default(),
+        }
+    }
+
+    pub fn html_dir(&self) -> &Path {
+        self.html_dir.as_deref().unwrap_or(Path::new("."))
+    }
+
+    pub fn pack_layout(&self) -> &str {
+        self.pack_layout.as_deref().unwrap_or(DEFAULT_PACK_LAYOUT)
+    }
+
+    pub fn polling(&self) -> PollOptions {
+        let default = PollOptions::default();
+        PollOptions {
+            interval: self.poll_interval.map_or(default.interval, |d| d.0),
+            timeout: self.poll_timeout.map_or(default.timeout, |d| d.0),
+        }
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl.map_or(DEFAULT_CACHE_TTL, |d| d.0)
+    }
+}
+
+/// Profile names end up in paths, so they are kept simple
+pub(crate) fn validate_profile_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Invalid profile name {}, use letters, digits, - and _ only",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Expands `{slug}`, `{lang}` and `{ext}` in a filename or path template
+pub(crate) fn render_template(template: &str, title_slug: &str, language: Language) -> String {
+    template
+        .replace("{slug}", title_slug)
+        .replace("{lang}", &language.to_string())
+        .replace("{ext}", language.extension())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CookieSource {
+    /// Environment variable holding the cookie
+    Env(String),
+    /// File containing the cookie
+    File(PathBuf),
+    /// Cookie store of a browser profile
+    Browser(BrowserProfile),
+    /// Cookie saved by `auth login`
+    Stored,
+}
+
+impl Default for CookieSource {
+    fn default() -> Self {
+        CookieSource::Env(LC_COOKIE_ENV_KEY.to_string())
+    }
+}
+
+impl CookieSource {
+    /// Whether reading the cookie may ask for a passphrase or copy
+    /// the cookie database of a browser
+    pub fn is_expensive(&self) -> bool {
+        match self {
+            CookieSource::Env(_) | CookieSource::File(_) => false,
+            CookieSource::Browser(_) => true,
+            CookieSource::Stored => credentials::needs_passphrase(),
+        }
+    }
+
+    /// Reads the cookie, browsers only give the cookies of `host`
+    pub fn read(&self, host: &str) -> Result<String> {
+        match self {
+            CookieSource::Env(key) => std::env::var_os(key)
+                .ok_or_else(|| {
+                    AuthError(format!(
+                        "{} is not set in the environment, set it or run `auth login`",
+                        key
+                    ))
+                })?
+                .into_string()
+                .map_err(|_| AuthError("Invalid Unicode found".to_string()).into()),
+            CookieSource::File(path) => std::fs::read_to_string(path)
+                .map(|cookie| cookie.trim().to_string())
+                .wrap_err_with(|| {
+                    AuthError(format!("Failed to read cookie from {}", path.display()))
+                }),
+            CookieSource::Browser(profile) => profile.read_cookie(host),
+            CookieSource::Stored => credentials::load(),
+        }
+    }
+}
+
+impl FromStr for CookieSource {
+    type Err = eyre::ErrReport;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("env", key)) if !key.is_empty() => Ok(CookieSource::Env(key.to_string())),
+            Some(("file", path)) if !path.is_empty() => Ok(CookieSource::File(path.into())),
+            None if s == "stored" => Ok(CookieSource::Stored),
+            _ => s.parse().map(CookieSource::Browser).map_err(|_| {
+                eyre::eyre!(
+                    "Unknown cookie source {}, expected env:<VAR>, file:<PATH>, stored, firefox[:<PROFILE>] or chromium[:<PROFILE>]",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+impl fmt::Display for CookieSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CookieSource::Env(key) => write!(f, "env:{}", key),
+            CookieSource::File(path) => write!(f, "file:{}", path.display()),
+            CookieSource::Browser(profile) => write!(f, "{}", profile),
+            CookieSource::Stored => write!(f, "stored"),
+        }
+    }
+}
+
+impl TryFrom<String> for CookieSource {
+    type Error = eyre::ErrReport;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<CookieSource> for String {
+    fn from(value: CookieSource) -> Self {
+        value.to_string()
+    }
+}
+
+/// Duration written as `500ms`, `30s`, `2m`, `12h` or `7d`, plain numbers are seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HumanDuration(pub Duration);
+
+impl FromStr for HumanDuration {
+    type Err = eyre::ErrReport;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let (value, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+            Some(idx) => s.split_at(idx),
+            None => (s, "s"),
+        };
+        let value: u64 = value
+            .trim()
+            .parse()
+            .map_err(|_| eyre::eyre!("Invalid duration {}, expected e.g. 500ms, 30s or 2m", s))?;
+        const HOUR: u64 = 60 * 60;
+        let seconds = |per_unit: u64| {
+            value
+                .checked_mul(per_unit)
+                .map(Duration::from_secs)
+                .ok_or_else(|| eyre::eyre!("Duration {} is too long", s))
+        };
+        let duration = match unit {
+            "ms" => Duration::from_millis(value),
+            "s" => Duration::from_secs(value),
+            "m" => seconds(60)?,
+            "h" => seconds(HOUR)?,
+            "d" => seconds(24 * HOUR)?,
+            _ => bail!(
+                "Unknown unit {} in duration {}, expected ms, s, m, h or d",
+                unit,
+                s
+            ),
+        };
+        Ok(HumanDuration(duration))
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = self.0.as_millis();
+        if !millis.is_multiple_of(1000) {
+            write!(f, "{}ms", millis)
+        } else if millis != 0 && millis.is_multiple_of(86_400_000) {
+            write!(f, "{}d", millis / 86_400_000)
+        } else if millis != 0 && millis.is_multiple_of(3_600_000) {
+            write!(f, "{}h", millis / 3_600_000)
+        } else if millis != 0 && millis.is_multiple_of(60_000) {
+            write!(f, "{}m", millis / 60_000)
+        } else {
+            write!(f, "{}s", millis / 1000)
+        }
+    }
+}
+
+impl TryFrom<String> for HumanDuration {
+    type Error = eyre::ErrReport;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<HumanDuration> for String {
+    fn from(value: HumanDuration) -> Self {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(duration: &str) -> Result<Duration> {
+        duration.parse::<HumanDuration>().map(|duration| duration.0)
+    }
+
+    #[test]
+    fn parses_durations_with_units() {
+        assert_eq!(parse("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse(" 2m ").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse("12h").unwrap(), Duration::from_secs(12 * 60 * 60));
+        assert_eq!(parse("7d").unwrap(), DEFAULT_CACHE_TTL);
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        assert!(parse("").is_err());
+        assert!(parse("-1s").is_err());
+        assert!(parse("1w").is_err());
+        assert!(parse("1.5h").is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert!(parse("999999999999999999d").is_err());
+        assert!(parse("99999999999999999d").is_err());
+        assert!(parse(&format!("{}m", u64::MAX)).is_err());
+        assert!(parse(&format!("{}h", u64::MAX / 60)).is_err());
+        assert!(parse(&format!("{}s", u64::MAX)).is_ok());
+    }
+
+    #[test]
+    fn durations_round_trip_through_display() {
+        for duration in ["500ms", "30s", "2m", "12h", "7d"] {
+            let parsed: HumanDuration = duration.parse().unwrap();
+            assert_eq!(parsed.to_string().parse::<HumanDuration>().unwrap(), parsed);
+        }
+    }
+}
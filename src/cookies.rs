@@ -0,0 +1,153 @@
+//! Session cookies, parsed from what the user gives or read from the
+//! cookie store of a browser profile
+//!
+//! The stores are SQLite databases locked by the running browser,
+//! so they are copied before being read.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::output::AuthError;
+
+use eyre::{bail, Context, Result};
+
+mod chromium;
+mod firefox;
+mod jar;
+
+pub use jar::CookieJar;
+
+const SESSION_COOKIE: &str = "LEETCODE_SESSION";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Browser {
+    Firefox,
+    Chromium,
+}
+
+/// Profile of a browser, its default one when `profile` is `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserProfile {
+    pub browser: Browser,
+    /// Name or path of the profile
+    pub profile: Option<String>,
+}
+
+/// A cookie as stored by the browser
+struct StoredCookie {
+    host: String,
+    name: String,
+    value: String,
+}
+
+impl BrowserProfile {
+    /// Cookie header with the cookies of `host`, e.g. `leetcode.com`
+    pub fn read_cookie(&self, host: &str) -> Result<String> {
+        let cookies = match self.browser {
+            Browser::Firefox => firefox::read_cookies(self.profile.as_deref())?,
+            Browser::Chromium => chromium::read_cookies(self.profile.as_deref(), host)?,
+        };
+        let cookies = cookies
+            .into_iter()
+            .filter(|cookie| domain_matches(&cookie.host, host))
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>();
+        if !cookies
+            .iter()
+            .any(|cookie| cookie.starts_with(&format!("{}=", SESSION_COOKIE)))
+        {
+            bail!(AuthError(format!(
+                "No LeetCode session found in the {} profile, log in to {} with it first",
+                self, host
+            )));
+        }
+        Ok(cookies.join("; "))
+    }
+}
+
+/// Whether a cookie set for `domain` is sent to `host`
+fn domain_matches(domain: &str, host: &str) -> bool {
+    match domain.strip_prefix('.') {
+        Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+        None => host == domain,
+    }
+}
+
+/// Copies the database at `path`, with its write-ahead log, into a temporary
+/// directory and opens the copy
+fn open_copy(path: &Path) -> Result<(tempfile::TempDir, rusqlite::Connection)> {
+    let dir = tempfile::tempdir()?;
+    let copy = dir.path().join("cookies.sqlite");
+    std::fs::copy(path, &copy)
+        .wrap_err_with(|| AuthError(format!("Failed to read cookies from {}", path.display())))?;
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    let wal = PathBuf::from(wal);
+    if wal.is_file() {
+        std::fs::copy(&wal, dir.path().join("cookies.sqlite-wal"))?;
+    }
+    let connection = rusqlite::Connection::open(&copy)?;
+    Ok((dir, connection))
+}
+
+/// Profile given by path, or by name inside `root`
+fn profile_dir(root: &Path, profile: &str) -> PathBuf {
+    let path = Path::new(profile);
+    if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        root.join(profile)
+    }
+}
+
+impl FromStr for BrowserProfile {
+    type Err = eyre::ErrReport;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (browser, profile) = match s.split_once(':') {
+            Some((browser, profile)) => (browser, Some(profile.to_string())),
+            None => (s, None),
+        };
+        let browser = match browser {
+            "firefox" => Browser::Firefox,
+            "chromium" => Browser::Chromium,
+            _ => bail!("Unknown browser {}, expected firefox or chromium", browser),
+        };
+        Ok(BrowserProfile {
+            browser,
+            profile: profile.filter(|profile| !profile.is_empty()),
+        })
+    }
+}
+
+impl fmt::Display for BrowserProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let browser = match self.browser {
+            Browser::Firefox => "firefox",
+            Browser::Chromium => "chromium",
+        };
+        match &self.profile {
+            Some(profile) => write!(f, "{}:{}", browser, profile),
+            None => write!(f, "{}", browser),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_cookie_domains() {
+        assert!(domain_matches(".leetcode.com", "leetcode.com"));
+        assert!(domain_matches(".leetcode.com", "www.leetcode.com"));
+        assert!(domain_matches("leetcode.com", "leetcode.com"));
+        assert!(!domain_matches("leetcode.com", "www.leetcode.com"));
+        assert!(!domain_matches(".leetcode.com", "evil-leetcode.com"));
+        assert!(!domain_matches("evil-leetcode.com", "leetcode.com"));
+        assert!(!domain_matches(".leetcode.com", "leetcode.com.evil.com"));
+    }
+}
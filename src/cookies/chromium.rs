@@ -0,0 +1,198 @@
+use std::path::{Path, PathBuf};
+
+use super::{domain_matches, open_copy, profile_dir, StoredCookie};
+use crate::output::AuthError;
+
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use eyre::{bail, Context, Result};
+
+const DEFAULT_PROFILE: &str = "Default";
+/// Password of the "Chromium Safe Storage" keyring entry, used for v11 cookies
+const CHROMIUM_KEY_ENV: &str = "LC_CHROMIUM_KEY";
+/// Password of v10 cookies, and of v11 ones without a keyring
+const BASIC_PASSWORD: &str = "peanuts";
+const SALT: &[u8] = b"saltysalt";
+const IV: [u8; 16] = [b' '; 16];
+const ITERATIONS: u32 = if cfg!(target_os = "macos") { 1003 } else { 1 };
+/// Since this database version values are prefixed by the SHA-256 of their domain
+const DOMAIN_HASH_VERSION: i64 = 24;
+const DOMAIN_HASH_LEN: usize = 32;
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+/// Cookies of the profile which are sent to `host`, decrypted
+pub(super) fn read_cookies(profile: Option<&str>, host: &str) -> Result<Vec<StoredCookie>> {
+    let dir = match profile {
+        Some(profile) if Path::new(profile).is_dir() => PathBuf::from(profile),
+        profile => {
+            let Some(root) = chromium_root() else {
+                bail!(AuthError("No Chromium installation found".to_string()));
+            };
+            profile_dir(&root, profile.unwrap_or(DEFAULT_PROFILE))
+        }
+    };
+    let Some(db) = [dir.join("Network").join("Cookies"), dir.join("Cookies")]
+        .into_iter()
+        .find(|path| path.is_file())
+    else {
+        bail!(AuthError(format!(
+            "No Chromium cookies found in {}",
+            dir.display()
+        )));
+    };
+
+    let (_dir, connection) = open_copy(&db)?;
+    let version: i64 = connection
+        .query_row(
+            "SELECT CAST(value AS INTEGER) FROM meta WHERE key = 'version'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    let keys = keys();
+
+    let mut statement =
+        connection.prepare("SELECT host_key, name, value, encrypted_value FROM cookies")?;
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Vec<u8>>(3)?,
+        ))
+    })?;
+    let mut cookies = Vec::new();
+    for row in rows {
+        let (cookie_host, name, value, encrypted) = row?;
+        // other cookies are left alone, they may use keys we don't have
+        if !domain_matches(&cookie_host, host) {
+            continue;
+        }
+        let value = if encrypted.is_empty() {
+            value
+        } else {
+            decrypt(&keys, &encrypted, version)
+                .wrap_err_with(|| format!("Failed to decrypt the {} cookie", name))?
+        };
+        cookies.push(StoredCookie {
+            host: cookie_host,
+            name,
+            value,
+        });
+    }
+    Ok(cookies)
+}
+
+/// Directory holding the profiles
+fn chromium_root() -> Option<PathBuf> {
+    let root = if cfg!(target_os = "macos") {
+        dirs::config_dir()?.join("Chromium")
+    } else if cfg!(windows) {
+        dirs::data_local_dir()?.join("Chromium").join("User Data")
+    } else {
+        dirs::config_dir()?.join("chromium")
+    };
+    root.is_dir().then_some(root)
+}
+
+/// Candidate keys, the supplied keyring password first
+fn keys() -> Vec<[u8; 16]> {
+    let supplied = std::env::var(CHROMIUM_KEY_ENV).ok();
+    supplied
+        .iter()
+        .map(String::as_str)
+        .chain([BASIC_PASSWORD, ""])
+        .map(|password| {
+            let mut key = [0u8; 16];
+            pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password.as_bytes(), SALT, ITERATIONS, &mut key);
+            key
+        })
+        .collect()
+}
+
+fn decrypt(keys: &[[u8; 16]], encrypted: &[u8], version: i64) -> Result<String> {
+    if cfg!(windows) {
+        bail!(AuthError(
+            "Chromium cookies can't be decrypted on Windows, use env: or file: instead".to_string()
+        ));
+    }
+    let data = match encrypted.get(..3) {
+        Some(b"v10") | Some(b"v11") => &encrypted[3..],
+        _ => bail!("Unknown encryption of Chromium cookie"),
+    };
+    for key in keys {
+        let Ok(mut plain) =
+            Aes128CbcDec::new(key.into(), &IV.into()).decrypt_padded_vec_mut::<Pkcs7>(data)
+        else {
+            continue;
+        };
+        if version >= DOMAIN_HASH_VERSION && plain.len() >= DOMAIN_HASH_LEN {
+            plain.drain(..DOMAIN_HASH_LEN);
+        }
+        if let Ok(value) = String::from_utf8(plain) {
+            return Ok(value);
+        }
+    }
+    bail!(AuthError(format!(
+        "Wrong key for the Chromium cookies, set {} to the \"Chromium Safe Storage\" password of your keyring",
+        CHROMIUM_KEY_ENV
+    )))
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    /// PBKDF2-HMAC-SHA1 of "peanuts" with a single iteration, the key of v10
+    /// cookies on Linux
+    const PEANUTS_KEY: [u8; 16] = [
+        0xfd, 0x62, 0x1f, 0xe5, 0xa2, 0xb4, 0x02, 0x53, 0x9d, 0xfa, 0x14, 0x7c, 0xa9, 0x27, 0x27,
+        0x78,
+    ];
+
+    /// `prefix` followed by the bytes of a hex string
+    fn encrypted(prefix: &[u8], hex: &str) -> Vec<u8> {
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap());
+        prefix.iter().copied().chain(bytes).collect()
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn derives_the_key_of_v10_cookies() {
+        assert!(keys().contains(&PEANUTS_KEY));
+    }
+
+    #[test]
+    fn decrypts_v10_cookies() {
+        // "abc123" encrypted with AES-128-CBC, the key above and an IV of spaces
+        let cookie = encrypted(b"v10", "b3b6d534338f5a3d7d9adc5e33da6a99");
+        assert_eq!(decrypt(&[PEANUTS_KEY], &cookie, 0).unwrap(), "abc123");
+        assert_eq!(
+            decrypt(&[[0; 16], PEANUTS_KEY], &cookie, 0).unwrap(),
+            "abc123"
+        );
+        assert!(decrypt(&[[0; 16]], &cookie, 0).is_err());
+        assert!(decrypt(
+            &[PEANUTS_KEY],
+            &encrypted(b"v20", "b3b6d534338f5a3d7d9adc5e33da6a99"),
+            0
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn strips_the_domain_hash_of_newer_databases() {
+        // the SHA-256 of ".leetcode.com" followed by "abc123"
+        let cookie = encrypted(
+            b"v10",
+            "c71ad0955bb37e0e0b97fa115ebcc39a1c46c206123cb4df7e5a84c202b8046d\
+             d3527f609fde345f50e342f4e694bb31",
+        );
+        assert_eq!(
+            decrypt(&[PEANUTS_KEY], &cookie, DOMAIN_HASH_VERSION).unwrap(),
+            "abc123"
+        );
+    }
+}
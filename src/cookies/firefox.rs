@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+
+use super::{open_copy, profile_dir, StoredCookie};
+use crate::output::AuthError;
+
+use eyre::{bail, Result};
+
+const PROFILES_INI: &str = "profiles.ini";
+const COOKIES_DB: &str = "cookies.sqlite";
+
+pub(super) fn read_cookies(profile: Option<&str>) -> Result<Vec<StoredCookie>> {
+    let dir = match profile {
+        Some(profile) if Path::new(profile).is_dir() => PathBuf::from(profile),
+        profile => {
+            let Some(root) = firefox_root() else {
+                bail!(AuthError("No Firefox installation found".to_string()));
+            };
+            find_profile(&root, profile)?
+        }
+    };
+    let db = dir.join(COOKIES_DB);
+    if !db.is_file() {
+        bail!(AuthError(format!(
+            "No Firefox cookies found in {}",
+            dir.display()
+        )));
+    }
+
+    let (_dir, connection) = open_copy(&db)?;
+    let mut statement = connection.prepare("SELECT host, name, value FROM moz_cookies")?;
+    let cookies = statement
+        .query_map([], |row| {
+            Ok(StoredCookie {
+                host: row.get(0)?,
+                name: row.get(1)?,
+                value: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(cookies)
+}
+
+/// Directory holding `profiles.ini`
+fn firefox_root() -> Option<PathBuf> {
+    let candidates = if cfg!(target_os = "macos") {
+        vec![dirs::config_dir()?.join("Firefox")]
+    } else if cfg!(windows) {
+        vec![dirs::config_dir()?.join("Mozilla").join("Firefox")]
+    } else {
+        let home = dirs::home_dir()?;
+        vec![
+            home.join(".mozilla/firefox"),
+            home.join("snap/firefox/common/.mozilla/firefox"),
+            home.join(".var/app/org.mozilla.firefox/.mozilla/firefox"),
+        ]
+    };
+    candidates
+        .into_iter()
+        .find(|dir| dir.join(PROFILES_INI).is_file())
+}
+
+/// Profile named `profile`, or the one Firefox starts with by default
+fn find_profile(root: &Path, profile: Option<&str>) -> Result<PathBuf> {
+    let ini = std::fs::read_to_string(root.join(PROFILES_INI))?;
+    let sections = parse_ini(&ini);
+    let path_of = |section: &[(String, String)]| {
+        let get = |key: &str| {
+            section
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.as_str())
+        };
+        let path = get("Path")?;
+        Some(match get("IsRelative") {
+            Some("0") => PathBuf::from(path),
+            _ => root.join(path),
+        })
+    };
+    let profiles = sections
+        .iter()
+        .filter(|(name, _)| name.starts_with("Profile"))
+        .map(|(_, section)| section);
+
+    if let Some(profile) = profile {
+        let found = profiles.clone().find(|section| {
+            section
+                .iter()
+                .any(|(key, value)| (key == "Name" || key == "Path") && value == profile)
+        });
+        return match found.and_then(|section| path_of(section)) {
+            Some(path) => Ok(path),
+            None => Ok(profile_dir(root, profile)),
+        };
+    }
+
+    // the install section names the profile in use since Firefox 67
+    let installed = sections
+        .iter()
+        .filter(|(name, _)| name.starts_with("Install"))
+        .find_map(|(_, section)| {
+            section
+                .iter()
+                .find(|(key, _)| key == "Default")
+                .map(|(_, path)| root.join(path))
+        });
+    let default = || {
+        profiles
+            .clone()
+            .find(|section| section.iter().any(|(k, v)| k == "Default" && v == "1"))
+            .or_else(|| profiles.clone().next())
+            .and_then(|section| path_of(section))
+    };
+    match installed.or_else(default) {
+        Some(path) => Ok(path),
+        None => bail!(AuthError("No Firefox profile found".to_string())),
+    }
+}
+
+/// Sections of an INI file with their key value pairs, in order
+fn parse_ini(ini: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in ini.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.to_string(), Vec::new()));
+        } else if let (Some((key, value)), Some((_, section))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            section.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES: &str = "[General]
+StartWithLastProfile=1
+
+[Profile1]
+Name=work
+IsRelative=0
+Path=/home/me/work-profile
+
+[Profile0]
+Name=default
+IsRelative=1
+Path=Profiles/abc.default
+Default=1
+";
+
+    fn root(ini: &str) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join(PROFILES_INI), ini).unwrap();
+        root
+    }
+
+    #[test]
+    fn parses_ini_sections() {
+        let sections = parse_ini(PROFILES);
+        let names: Vec<&str> = sections.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["General", "Profile1", "Profile0"]);
+        assert_eq!(sections[2].1[3], ("Default".to_string(), "1".to_string()));
+    }
+
+    #[test]
+    fn finds_the_default_profile() {
+        let root = root(PROFILES);
+        assert_eq!(
+            find_profile(root.path(), None).unwrap(),
+            root.path().join("Profiles/abc.default")
+        );
+
+        let installed = format!(
+            "{}\n[Install4F96D1932A9F858E]\nDefault=Profiles/xyz.release\n",
+            PROFILES
+        );
+        let root = self::root(&installed);
+        assert_eq!(
+            find_profile(root.path(), None).unwrap(),
+            root.path().join("Profiles/xyz.release")
+        );
+    }
+
+    #[test]
+    fn finds_profiles_by_name() {
+        let root = root(PROFILES);
+        assert_eq!(
+            find_profile(root.path(), Some("work")).unwrap(),
+            PathBuf::from("/home/me/work-profile")
+        );
+        assert_eq!(
+            find_profile(root.path(), Some("Profiles/abc.default")).unwrap(),
+            root.path().join("Profiles/abc.default")
+        );
+        assert!(find_profile(&root.path().join("missing"), None).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use std::time::{Duration, SystemTime};
+
+use super::{domain_matches, SESSION_COOKIE};
+use crate::output::AuthError;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use eyre::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+const CSRF_COOKIE: &str = "csrftoken";
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+/// Prefix curl gives the domain of HttpOnly cookies in cookies.txt
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// Cookies sent to LeetCode, in the order they were given
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CookieJar {
+    cookies: Vec<(String, String)>,
+}
+
+/// Claims of the session JWT telling when it expires
+#[derive(Deserialize)]
+struct SessionClaims {
+    exp: Option<u64>,
+    /// LeetCode's own expiry claim
+    expired_time_: Option<u64>,
+}
+
+impl CookieJar {
+    /// Parses a `Cookie` header, a Netscape cookies.txt file or a JSON export
+    /// of a browser extension. Cookies of files which aren't sent to `host`
+    /// are left out
+    pub fn parse(input: &str, host: &str) -> Result<Self> {
+        let input = input.trim();
+        let jar = if input.starts_with('[') || input.starts_with('{') {
+            Self::from_json(input, host)?
+        } else if is_netscape(input) {
+            Self::from_netscape(input, host)
+        } else {
+            Self::from_header(input)
+        };
+        if jar.cookies.is_empty() {
+            bail!(AuthError(format!("No cookies for {} found", host)));
+        }
+        Ok(jar)
+    }
+
+    fn from_header(header: &str) -> Self {
+        let header = match header.split_once(':') {
+            Some((name, rest)) if name.trim().eq_ignore_ascii_case("cookie") => rest,
+            _ => header,
+        };
+        let mut jar = Self::default();
+        for pair in header.split(';') {
+            if let Some((name, value)) = pair.split_once('=') {
+                jar.insert(name, unquote(value.trim()));
+            }
+        }
+        jar
+    }
+
+    fn from_netscape(file: &str, host: &str) -> Self {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        let mut jar = Self::default();
+        for line in file.lines() {
+            let line = line.strip_prefix(HTTP_ONLY_PREFIX).unwrap_or(line);
+            if line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, subdomains, _path, _secure, expiry, name, value] = fields[..] else {
+                continue;
+            };
+            let domain = if subdomains.eq_ignore_ascii_case("TRUE") && !domain.starts_with('.') {
+                format!(".{}", domain)
+            } else {
+                domain.to_string()
+            };
+            // 0 marks a session cookie
+            let expired = expiry.parse::<u64>().is_ok_and(|exp| exp != 0 && exp < now);
+            if !expired && domain_matches(&domain, host) {
+                jar.insert(name, value.trim_end_matches('\r'));
+            }
+        }
+        jar
+    }
+
+    /// Either a list of cookie objects, such a list under `cookies`,
+    /// or an object mapping names to values
+    fn from_json(json: &str, host: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json).wrap_err_with(|| {
+            AuthError("The cookie looks like JSON but can't be parsed".to_string())
+        })?;
+        let list = match &value {
+            Value::Array(list) => Some(list),
+            Value::Object(object) => object.get("cookies").and_then(Value::as_array),
+            _ => None,
+        };
+        let mut jar = Self::default();
+        match (list, &value) {
+            (Some(list), _) => {
+                for cookie in list {
+                    let field = |key: &str| cookie.get(key).and_then(Value::as_str);
+                    let (Some(name), Some(value)) = (field("name"), field("value")) else {
+                        continue;
+                    };
+                    let sent = match field("domain") {
+                        Some(domain) => {
+                            let host_only = cookie.get("hostOnly").and_then(Value::as_bool);
+                            let domain = match host_only {
+                                Some(false) if !domain.starts_with('.') => format!(".{}", domain),
+                                _ => domain.to_string(),
+                            };
+                            domain_matches(&domain, host)
+                        }
+                        None => true,
+                    };
+                    if sent {
+                        jar.insert(name, value);
+                    }
+                }
+            }
+            (None, Value::Object(object)) => {
+                for (name, value) in object {
+                    if let Some(value) = value.as_str() {
+                        jar.insert(name, value);
+                    }
+                }
+            }
+            _ => bail!(AuthError(
+                "Expected a list of cookies in the JSON export".to_string()
+            )),
+        }
+        Ok(jar)
+    }
+
+    /// Adds a cookie, the first one given wins over later ones of the same name
+    fn insert(&mut self, name: &str, value: &str) {
+        let name = name.trim();
+        if name.is_empty() || self.get(name).is_some() {
+            return;
+        }
+        self.cookies.push((name.to_string(), value.to_string()));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|(cookie, _)| cookie == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn csrf_token(&self) -> Option<&str> {
+        self.get(CSRF_COOKIE).filter(|token| !token.is_empty())
+    }
+
+    pub fn session(&self) -> Option<&str> {
+        self.get(SESSION_COOKIE)
+            .filter(|session| !session.is_empty())
+    }
+
+    /// Fails naming the cookies LeetCode needs which are missing
+    pub fn require_session(&self) -> Result<()> {
+        let missing: Vec<&str> = [
+            (CSRF_COOKIE, self.csrf_token()),
+            (SESSION_COOKIE, self.session()),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.is_none())
+        .map(|(name, _)| name)
+        .collect();
+        if !missing.is_empty() {
+            bail!(AuthError(format!(
+                "The cookie has no {}, copy it again from a logged in LeetCode tab",
+                missing.join(" nor ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Value of the `Cookie` header
+    pub fn header(&self) -> String {
+        self.cookies
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Whether the session is known to have expired
+    pub fn is_expired(&self) -> bool {
+        self.session_expiry()
+            .is_some_and(|expiry| expiry <= SystemTime::now())
+    }
+
+    /// Expiry of the session, decoded from the `LEETCODE_SESSION` JWT.
+    /// The signature isn't checked, LeetCode does that
+    pub fn session_expiry(&self) -> Option<SystemTime> {
+        let payload = self.session()?.split('.').nth(1)?;
+        let claims: SessionClaims =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?)
+                .ok()?;
+        let expiry = claims.exp.or(claims.expired_time_)?;
+        // an expiry too far away to be represented is as good as unknown
+        SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(expiry))
+    }
+}
+
+fn is_netscape(input: &str) -> bool {
+    input.starts_with(NETSCAPE_HEADER)
+        || input.lines().any(|line| {
+            let line = line.strip_prefix(HTTP_ONLY_PREFIX).unwrap_or(line);
+            !line.starts_with('#') && line.split('\t').count() == 7
+        })
+}
+
+/// Cookie values may be wrapped in double quotes
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "leetcode.com";
+
+    /// Session cookie holding `claims`, with a dummy header and signature
+    fn session(claims: &str) -> String {
+        format!(
+            "{}={}.{}.signature",
+            SESSION_COOKIE,
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256"}"#),
+            URL_SAFE_NO_PAD.encode(claims)
+        )
+    }
+
+    #[test]
+    fn parses_cookie_headers() {
+        let jar = CookieJar::parse(
+            r#"Cookie: csrftoken="abc"; LEETCODE_SESSION=xyz; csrftoken=later"#,
+            HOST,
+        )
+        .unwrap();
+        assert_eq!(jar.csrf_token(), Some("abc"));
+        assert_eq!(jar.session(), Some("xyz"));
+        assert!(jar.require_session().is_ok());
+    }
+
+    #[test]
+    fn parses_netscape_files_for_the_host() {
+        let file = [
+            NETSCAPE_HEADER,
+            ".leetcode.com\tTRUE\t/\tTRUE\t0\tcsrftoken\tabc",
+            "#HttpOnly_leetcode.com\tFALSE\t/\tTRUE\t0\tLEETCODE_SESSION\txyz\r",
+            "leetcode.cn\tFALSE\t/\tTRUE\t0\tother\tnope",
+            ".leetcode.com\tTRUE\t/\tTRUE\t1\texpired\tnope",
+        ]
+        .join("\n");
+        let jar = CookieJar::parse(&file, HOST).unwrap();
+        assert_eq!(jar.header(), "csrftoken=abc; LEETCODE_SESSION=xyz");
+    }
+
+    #[test]
+    fn parses_json_exports() {
+        let list = r#"[
+            {"name": "csrftoken", "value": "abc", "domain": "leetcode.com", "hostOnly": false},
+            {"name": "LEETCODE_SESSION", "value": "xyz", "domain": "www.leetcode.com"},
+            {"name": "other", "value": "nope", "domain": "example.com"}
+        ]"#;
+        let jar = CookieJar::parse(list, "www.leetcode.com").unwrap();
+        assert_eq!(jar.header(), "csrftoken=abc; LEETCODE_SESSION=xyz");
+
+        let object = r#"{"csrftoken": "abc", "LEETCODE_SESSION": "xyz"}"#;
+        let jar = CookieJar::parse(object, HOST).unwrap();
+        assert_eq!(jar.csrf_token(), Some("abc"));
+        assert_eq!(jar.session(), Some("xyz"));
+
+        assert!(CookieJar::parse("{not json", HOST).is_err());
+        assert!(CookieJar::parse("[]", HOST).is_err());
+    }
+
+    #[test]
+    fn decodes_the_session_expiry() {
+        let jar = CookieJar::parse(&session(r#"{"exp": 1700000000}"#), HOST).unwrap();
+        assert_eq!(
+            jar.session_expiry(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert!(jar.is_expired());
+
+        let jar = CookieJar::parse(&session(r#"{"expired_time_": 4102444800}"#), HOST).unwrap();
+        assert!(!jar.is_expired());
+    }
+
+    #[test]
+    fn unknown_expiry_of_malformed_sessions() {
+        for cookie in [
+            session(&format!(r#"{{"exp": {}}}"#, u64::MAX)),
+            session("{}"),
+            session("not json"),
+            format!("{}=no-dots", SESSION_COOKIE),
+        ] {
+            let jar = CookieJar::parse(&cookie, HOST).unwrap();
+            assert_eq!(jar.session_expiry(), None, "{}", cookie);
+            assert!(!jar.is_expired());
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Session cookie saved by `auth login`
+//!
+//! The cookie is kept in `credentials` next to the global config, readable
+//! by its owner only. When saved with a passphrase it's encrypted with
+//! AES-256-GCM under a PBKDF2 key, and the passphrase is asked for, or read
+//! from `LC_PASSPHRASE`, whenever the cookie is needed.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::config::Config;
+use crate::output::AuthError;
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use eyre::{bail, Context, Result};
+
+const CREDENTIALS_FILE: &str = "credentials";
+/// Directory holding a cookie for each profile
+const PROFILES_DIR: &str = "profiles";
+const PASSPHRASE_ENV: &str = "LC_PASSPHRASE";
+/// Starts an encrypted file, followed by the base64 of salt, nonce and ciphertext
+const ENCRYPTED_PREFIX: &str = "encrypted:v1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const ITERATIONS: u32 = 600_000;
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Keeps the cookie of `profile` apart from the others, only the first
+/// call has an effect
+pub fn set_profile(profile: &str) {
+    let _ = PROFILE.set(profile.to_string());
+}
+
+pub fn path() -> Option<PathBuf> {
+    profile_path(PROFILE.get().map(String::as_str))
+}
+
+/// File of the cookie of `profile`, or of the one used without a profile
+pub fn profile_path(profile: Option<&str>) -> Option<PathBuf> {
+    let dir = Config::dir()?;
+    Some(match profile {
+        Some(profile) => dir.join(PROFILES_DIR).join(profile).join(CREDENTIALS_FILE),
+        None => dir.join(CREDENTIALS_FILE),
+    })
+}
+
+pub fn exists() -> bool {
+    path().is_some_and(|path| path.is_file())
+}
+
+/// Whether the saved cookie is encrypted, reading it then asks for the
+/// passphrase unless `LC_PASSPHRASE` is set
+pub fn needs_passphrase() -> bool {
+    std::env::var_os(PASSPHRASE_ENV).is_none()
+        && path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .is_some_and(|contents| contents.starts_with(ENCRYPTED_PREFIX))
+}
+
+/// Saved cookie, decrypted when needed
+pub fn load() -> Result<String> {
+    let path = require_path()?;
+    if !path.is_file() {
+        bail!(AuthError(
+            "Not logged in, run `auth login` first".to_string()
+        ));
+    }
+    let contents = std::fs::read_to_string(&path)
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    match contents.strip_prefix(ENCRYPTED_PREFIX) {
+        Some(sealed) => decrypt(sealed.trim(), &passphrase(false)?),
+        None => Ok(contents.trim().to_string()),
+    }
+}
+
+/// Saves `cookie`, encrypted with a passphrase when `encrypt` is set.
+/// Returns where it was saved
+pub fn save(cookie: &str, encrypt: bool) -> Result<PathBuf> {
+    let path = require_path()?;
+    let contents = if encrypt {
+        format!(
+            "{}{}\n",
+            ENCRYPTED_PREFIX,
+            encrypt_cookie(cookie, &passphrase(true)?)?
+        )
+    } else {
+        format!("{}\n", cookie)
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_private(&path, &contents)
+        .wrap_err_with(|| format!("Failed to save the cookie to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Removes the saved cookie, returns whether there was one
+pub fn remove() -> Result<bool> {
+    remove_file(&require_path()?)
+}
+
+/// Removes the saved cookie of `profile` along with its directory
+pub fn remove_profile(profile: &str) -> Result<bool> {
+    let path = profile_path(Some(profile))
+        .ok_or_else(|| eyre::eyre!("Could not locate the config directory"))?;
+    let removed = remove_file(&path)?;
+    if let Some(dir) = path.parent() {
+        // only gone when empty
+        let _ = std::fs::remove_dir(dir);
+    }
+    Ok(removed)
+}
+
+fn remove_file(path: &Path) -> Result<bool> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+    std::fs::remove_file(path)?;
+    Ok(true)
+}
+
+fn require_path() -> Result<PathBuf> {
+    path().ok_or_else(|| eyre::eyre!("Could not locate the config directory"))
+}
+
+/// Writes through a fresh file only its owner may read, then moves it in place
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    // a leftover could have been created with looser permissions
+    let _ = std::fs::remove_file(&tmp);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Passphrase from the environment, or asked for on the terminal
+fn passphrase(confirm: bool) -> Result<String> {
+    let passphrase = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => ask_passphrase(confirm)?,
+    };
+    if passphrase.is_empty() {
+        bail!("The passphrase can't be empty");
+    }
+    Ok(passphrase)
+}
+
+fn ask_passphrase(confirm: bool) -> Result<String> {
+    let no_terminal = || {
+        AuthError(format!(
+            "Could not ask for the passphrase, set {} when there's no terminal",
+            PASSPHRASE_ENV
+        ))
+    };
+    let passphrase = rpassword::prompt_password("Passphrase: ").wrap_err_with(no_terminal)?;
+    if confirm {
+        let again = rpassword::prompt_password("Repeat passphrase: ").wrap_err_with(no_terminal)?;
+        if again != passphrase {
+            bail!("The passphrases don't match");
+        }
+    }
+    Ok(passphrase)
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+    let key =
+        pbkdf2::pbkdf2_hmac_array::<sha2::Sha256, 32>(passphrase.as_bytes(), salt, ITERATIONS);
+    Aes256Gcm::new(&key.into())
+}
+
+fn encrypt_cookie(cookie: &str, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(passphrase, &salt)
+        .encrypt(&nonce, cookie.as_bytes())
+        .map_err(|_| eyre::eyre!("Failed to encrypt the cookie"))?;
+    let sealed = [&salt[..], &nonce[..], &ciphertext].concat();
+    Ok(STANDARD.encode(sealed))
+}
+
+fn decrypt(sealed: &str, passphrase: &str) -> Result<String> {
+    let sealed = STANDARD
+        .decode(sealed)
+        .wrap_err("The saved cookie is corrupted, run `auth login` again")?;
+    if sealed.len() < SALT_LEN + NONCE_LEN {
+        bail!("The saved cookie is corrupted, run `auth login` again");
+    }
+    let (salt, rest) = sealed.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let plain = cipher(passphrase, salt)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AuthError("Wrong passphrase for the saved cookie".to_string()))?;
+    String::from_utf8(plain).wrap_err("The saved cookie is corrupted, run `auth login` again")
+}
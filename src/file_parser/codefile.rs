@@ -1,10 +1,9 @@
 use eyre::Result;
 
 use super::language::*;
-use std::{
-    path::{Path, PathBuf},
-    str::FromStr,
-};
+use super::problem_ref::ProblemRef;
+use crate::handlers::question::SCHEMA_EXTENSION;
+use std::path::{Path, PathBuf};
 
 pub struct CodeFile {
     pub language: Language,
@@ -63,15 +62,17 @@ impl CodeFile {
         Ok(code_file)
     }
 
-    fn is_valid_file<'a, P: AsRef<Path>>(path: &'a P) -> Option<(&'a str, Self)> {
-        let extension = path.as_ref().extension().and_then(|ext| ext.to_str())?;
+    fn is_valid_file<P: AsRef<Path>>(path: &P) -> Option<(&str, Self)> {
+        let file_name = path.as_ref().file_name()?.to_str()?;
+        // saved along with the code of database questions
+        if file_name.ends_with(&format!(".{}", SCHEMA_EXTENSION)) {
+            return None;
+        }
 
         Some((
-            path.as_ref()
-                .file_name()
-                .and_then(|filename| filename.to_str())?,
+            file_name,
             CodeFile {
-                language: Language::from_str(extension).ok()?,
+                language: Language::from_file_name(file_name)?,
                 path: path.as_ref().into(),
                 question_title: String::new(),
                 code: String::new(),
@@ -89,20 +90,13 @@ impl CodeFile {
             .unwrap_or_default();
 
         let end = code.find("#LCEND").unwrap_or(code.len());
-        let question_title = code[code.find("leetcode.com/problems/").ok_or_else(|| {
-            eyre::eyre!(
+        let question_title = match ProblemRef::find_in(code).transpose()? {
+            Some(ProblemRef::Url { slug, .. }) => slug,
+            _ => eyre::bail!(
                 "No leetcode problem found in the code file. \
         Please add the problem link in the code file using comments."
-            )
-        })?..]
-            .split_whitespace()
-            .next()
-            .expect("Should be Some since the find method succeed")
-            .split('/')
-            .skip(2)
-            .next()
-            .ok_or_else(|| eyre::eyre!("Invalid link, expected question identifier"))?
-            .to_string();
+            ),
+        };
         let parsed_code = code[start..end]
             .trim()
             .trim_end_matches(language.inline_comment_start())
@@ -1,6 +1,42 @@
-use std::{fmt, str::FromStr};
+use std::{collections::BTreeMap, fmt, str::FromStr, sync::OnceLock};
 
-#[derive(Debug, Default, Clone, Copy)]
+use crate::config::LanguageConfig;
+
+use eyre::{bail, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Languages of the `languages` config, defined before any language is parsed
+static DEFINITIONS: OnceLock<Vec<Definition>> = OnceLock::new();
+
+/// Language added, or builtin one extended, by the config. Its strings are
+/// leaked once so that they can be used like the builtin ones
+#[derive(Debug)]
+struct Definition {
+    /// LeetCode's `langSlug`
+    slug: &'static str,
+    /// Builtin language which is extended, a new one otherwise
+    builtin: Option<Language>,
+    /// Extensions recognized as the language, the first one is used for new files
+    extensions: Vec<&'static str>,
+    /// Other names of the language, e.g. for `--lang`
+    aliases: Vec<&'static str>,
+    /// File name of the solution for local runs, e.g. `main.zig`
+    source: &'static str,
+    comment: Option<&'static str>,
+    build: Option<&'static [&'static str]>,
+    run: Option<&'static [&'static str]>,
+}
+
+/// Commands of the config running solutions of a language locally
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LocalCommands {
+    /// File name of the solution for new languages
+    pub(crate) source: &'static str,
+    pub(crate) build: Option<&'static [&'static str]>,
+    pub(crate) run: Option<&'static [&'static str]>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Language {
     #[default]
     Rust,
@@ -16,16 +52,28 @@ pub enum Language {
     Csharp,
     Ruby,
     Scala,
-    PHP,
+    Php,
     Racket,
     Erlang,
     Elixir,
     Dart,
+    Mysql,
+    Mssql,
+    Oraclesql,
+    Postgresql,
+    Bash,
+    /// Pandas
+    Pythondata,
+    /// Defined in the `languages` config, by its position there
+    Custom(usize),
 }
 
 impl fmt::Display for Language {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&format!("{:?}", self).to_lowercase())
+        match self.definition() {
+            Some(definition) if definition.builtin.is_none() => f.write_str(definition.slug),
+            _ => f.write_str(&format!("{:?}", self).to_lowercase()),
+        }
     }
 }
 
@@ -33,7 +81,11 @@ impl FromStr for Language {
     type Err = eyre::ErrReport;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_ref() {
+        let name = s.to_lowercase();
+        if let Some(language) = Language::defined(&name) {
+            return Ok(language);
+        }
+        match name.as_ref() {
             "rs" | "rust" => Ok(Language::Rust),
             /* python creates ambiguity with Python2.7 and Python3
              * as both are supported by leetcode, so we only allow python3 */
@@ -49,18 +101,213 @@ impl FromStr for Language {
             "cs" | "csharp" => Ok(Language::Csharp),
             "rb" | "ruby" => Ok(Language::Ruby),
             "scala" => Ok(Language::Scala),
-            "php" => Ok(Language::PHP),
+            "php" => Ok(Language::Php),
             "rkt" | "racket" => Ok(Language::Racket),
             "erl" | "erlang" => Ok(Language::Erlang),
             "ex" | "elixer" => Ok(Language::Elixir),
             "dart" => Ok(Language::Dart),
-            _ => Err(eyre::eyre!("Unknown language")),
+            "sql" | "mysql" => Ok(Language::Mysql),
+            "tsql" | "mssql" => Ok(Language::Mssql),
+            "plsql" | "oraclesql" => Ok(Language::Oraclesql),
+            "pgsql" | "postgresql" => Ok(Language::Postgresql),
+            "sh" | "bash" => Ok(Language::Bash),
+            "pandas" | "pythondata" => Ok(Language::Pythondata),
+            _ => Err(eyre::eyre!("Unknown language {}", s)),
         }
     }
 }
 
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Language::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 impl Language {
-    pub fn extension(&self) -> &str {
+    pub const ALL: [Language; 24] = [
+        Language::Rust,
+        Language::Python3,
+        Language::Cpp,
+        Language::Java,
+        Language::C,
+        Language::Javascript,
+        Language::Go,
+        Language::Kotlin,
+        Language::Swift,
+        Language::Typescript,
+        Language::Csharp,
+        Language::Ruby,
+        Language::Scala,
+        Language::Php,
+        Language::Racket,
+        Language::Erlang,
+        Language::Elixir,
+        Language::Dart,
+        Language::Mysql,
+        Language::Mssql,
+        Language::Oraclesql,
+        Language::Postgresql,
+        Language::Bash,
+        Language::Pythondata,
+    ];
+
+    /// Defines the languages of the config, whose keys are LeetCode's
+    /// `langSlug`s. Only the first call has an effect
+    pub fn define(languages: &BTreeMap<String, LanguageConfig>) -> Result<()> {
+        let leak = |s: &str| -> &'static str { s.to_string().leak() };
+        let leak_command = |slug: &str, command: &Option<Vec<String>>| -> Result<_> {
+            match command {
+                Some(command) if command.is_empty() => {
+                    bail!("Language {} of the config has an empty command", slug)
+                }
+                Some(command) => Ok(Some(
+                    &*command
+                        .iter()
+                        .map(|arg| leak(arg))
+                        .collect::<Vec<_>>()
+                        .leak(),
+                )),
+                None => Ok(None),
+            }
+        };
+        let mut definitions = Vec::with_capacity(languages.len());
+        for (slug, config) in languages {
+            // nothing is defined yet, so only builtin languages are found
+            let builtin = Language::from_str(slug).ok();
+            let extensions: Vec<&'static str> = config
+                .extensions
+                .iter()
+                .map(|extension| leak(&extension.trim_start_matches('.').to_lowercase()))
+                .collect();
+            let extension = match (extensions.first(), builtin) {
+                (Some(extension), _) => *extension,
+                (None, Some(builtin)) => builtin.extension(),
+                (None, None) => bail!(
+                    "Language {} of the config needs at least one extension",
+                    slug
+                ),
+            };
+            if builtin.is_none() && config.comment.is_none() {
+                bail!(
+                    "Language {} of the config needs the comment starting its lines",
+                    slug
+                );
+            }
+            definitions.push(Definition {
+                slug: leak(&slug.to_lowercase()),
+                builtin,
+                extensions,
+                aliases: config
+                    .aliases
+                    .iter()
+                    .map(|alias| leak(&alias.to_lowercase()))
+                    .collect(),
+                source: leak(&format!("main.{}", extension)),
+                comment: config.comment.as_deref().map(leak),
+                build: leak_command(slug, &config.build)?,
+                run: leak_command(slug, &config.run)?,
+            });
+        }
+        let _ = DEFINITIONS.set(definitions);
+        Ok(())
+    }
+
+    /// Language of the config named `name`
+    fn defined(name: &str) -> Option<Language> {
+        DEFINITIONS
+            .get()?
+            .iter()
+            .enumerate()
+            .find(|(_, definition)| {
+                definition.slug == name
+                    || definition.extensions.contains(&name)
+                    || definition.aliases.contains(&name)
+            })
+            .map(|(idx, definition)| definition.builtin.unwrap_or(Language::Custom(idx)))
+    }
+
+    fn definition(&self) -> Option<&'static Definition> {
+        let definitions = DEFINITIONS.get()?;
+        match self {
+            Language::Custom(idx) => definitions.get(*idx),
+            builtin => definitions
+                .iter()
+                .find(|definition| definition.builtin == Some(*builtin)),
+        }
+    }
+
+    /// Builtin languages followed by the ones of the config
+    pub fn all() -> impl Iterator<Item = Language> {
+        let custom = DEFINITIONS.get().into_iter().flatten().enumerate();
+        Language::ALL.into_iter().chain(
+            custom
+                .filter(|(_, definition)| definition.builtin.is_none())
+                .map(|(idx, _)| Language::Custom(idx)),
+        )
+    }
+
+    /// Commands of the config to build and run solutions locally
+    pub(crate) fn local_commands(&self) -> Option<LocalCommands> {
+        let definition = self.definition()?;
+        if definition.build.is_none() && definition.run.is_none() {
+            return None;
+        }
+        Some(LocalCommands {
+            source: definition.source,
+            build: definition.build,
+            run: definition.run,
+        })
+    }
+
+    /// Language of a code file by the longest extension its name ends with,
+    /// so that `main.pandas.py` isn't taken for Python3
+    pub fn from_file_name(file_name: &str) -> Option<Language> {
+        Language::all()
+            .flat_map(|language| {
+                language
+                    .extensions()
+                    .map(move |extension| (language, extension))
+            })
+            .filter(|(_, extension)| {
+                file_name
+                    .strip_suffix(extension)
+                    .and_then(|stem| stem.strip_suffix('.'))
+                    .is_some_and(|stem| !stem.is_empty())
+            })
+            .max_by_key(|(_, extension)| extension.len())
+            .map(|(language, _)| language)
+            .or_else(|| {
+                let (_, extension) = file_name.rsplit_once('.')?;
+                Language::from_str(extension).ok()
+            })
+    }
+
+    /// Extensions of the config followed by the builtin one
+    fn extensions(self) -> impl Iterator<Item = &'static str> {
+        let configured = self.definition().map(|d| d.extensions.as_slice());
+        configured
+            .into_iter()
+            .flatten()
+            .copied()
+            .chain(Some(self.builtin_extension()).filter(|extension| !extension.is_empty()))
+    }
+
+    /// Extension of new code files
+    pub fn extension(&self) -> &'static str {
+        match self.definition().and_then(|d| d.extensions.first()) {
+            Some(extension) => extension,
+            None => self.builtin_extension(),
+        }
+    }
+
+    fn builtin_extension(&self) -> &'static str {
         match self {
             Language::Rust => "rs",
             Language::Python3 => "py",
@@ -75,22 +322,42 @@ impl Language {
             Language::Csharp => "cs",
             Language::Ruby => "rb",
             Language::Scala => "scala",
-            Language::PHP => "php",
+            Language::Php => "php",
             Language::Racket => "rkt",
             Language::Erlang => "erl",
             Language::Elixir => "ex",
             Language::Dart => "dart",
+            Language::Mysql => "sql",
+            Language::Mssql => "tsql",
+            Language::Oraclesql => "plsql",
+            Language::Postgresql => "pgsql",
+            Language::Bash => "sh",
+            Language::Pythondata => "pandas.py",
+            Language::Custom(_) => "",
         }
     }
 
     pub(crate) fn inline_comment_start(&self) -> &str {
         use Language::*;
+        if let Some(comment) = self.definition().and_then(|d| d.comment) {
+            return comment;
+        }
         match self {
             Rust | Cpp | C | Csharp | Javascript | Typescript | Kotlin | Java | Go | Scala
             | Swift | Dart => "//",
-            Python3 | Ruby | PHP | Elixir => "#",
+            Python3 | Ruby | Php | Elixir | Bash | Pythondata => "#",
             Racket => ";",
             Erlang => "%",
+            Mysql | Mssql | Oraclesql | Postgresql => "--",
+            Custom(_) => "",
         }
     }
+
+    /// Whether solutions are queries of a database question
+    pub(crate) fn is_database(&self) -> bool {
+        matches!(
+            self,
+            Language::Mysql | Language::Mssql | Language::Oraclesql | Language::Postgresql
+        )
+    }
 }
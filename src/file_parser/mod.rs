@@ -1,2 +1,3 @@
 pub mod codefile;
 pub(crate) mod language;
+pub(crate) mod problem_ref;
@@ -0,0 +1,196 @@
+use std::{fmt, str::FromStr};
+
+use eyre::Result;
+
+/// Marks the slug in the path of a problem url
+const PROBLEMS_SEGMENT: &str = "problems";
+const CONTEST_SEGMENT: &str = "contest";
+
+/// A problem as given on the command line or linked in a code file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemRef {
+    /// Problem url of leetcode.com or leetcode.cn, also within a contest or study plan
+    Url {
+        slug: String,
+        /// Host of the url, e.g. leetcode.cn
+        host: Option<String>,
+        /// Slug of the contest the url points into
+        contest: Option<String>,
+    },
+    /// Frontend number as shown on the problem set
+    Number(String),
+    /// Slug or title, looked up in the problem list
+    Name(String),
+}
+
+impl ProblemRef {
+    /// First valid problem url found in `text`, e.g. in the comments of a
+    /// code file. Malformed links are skipped, the error of the first one
+    /// is returned when no link is valid
+    pub fn find_in(text: &str) -> Option<Result<Self>> {
+        let mut first_error = None;
+        for word in text
+            .split_whitespace()
+            .filter(|word| word.contains("/problems/"))
+        {
+            match Self::from_url(word) {
+                Ok(problem) => return Some(Ok(problem)),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map(Err)
+    }
+
+    fn from_url(url: &str) -> Result<Self> {
+        let (host, path) = match url.split_once("://") {
+            Some((_, rest)) => match rest.split_once('/') {
+                Some((host, path)) => (Some(host), path),
+                None => (Some(rest), ""),
+            },
+            None => (None, url),
+        };
+        // study plans and the editor add ?envType=... to the links
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let Some(idx) = segments.iter().position(|s| *s == PROBLEMS_SEGMENT) else {
+            eyre::bail!("Invalid link, expected a /problems/ path");
+        };
+        // the scheme may be left out, as in leetcode.cn/problems/two-sum
+        let host = host.or_else(|| {
+            segments
+                .first()
+                .filter(|s| idx > 0 && s.contains('.'))
+                .copied()
+        });
+        let slug = segments
+            .get(idx + 1)
+            .ok_or_else(|| eyre::eyre!("Invalid link, expected question identifier"))?;
+        let contest = match idx.checked_sub(2).map(|start| &segments[start..idx]) {
+            Some([CONTEST_SEGMENT, contest]) => Some(contest.to_string()),
+            _ => None,
+        };
+        Ok(Self::Url {
+            slug: slug.to_string(),
+            host: host.map(|host| host.trim_start_matches("www.").to_string()),
+            contest,
+        })
+    }
+}
+
+impl FromStr for ProblemRef {
+    type Err = eyre::ErrReport;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            eyre::bail!("Expected a question");
+        }
+        if s.contains("://") || s.contains("/problems/") {
+            Self::from_url(s)
+        } else if s.chars().all(|c| c.is_ascii_digit()) {
+            Ok(Self::Number(s.to_string()))
+        } else {
+            Ok(Self::Name(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for ProblemRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemRef::Url { slug, .. } | ProblemRef::Name(slug) => f.write_str(slug),
+            ProblemRef::Number(number) => write!(f, "#{}", number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(slug: &str, host: Option<&str>, contest: Option<&str>) -> ProblemRef {
+        ProblemRef::Url {
+            slug: slug.to_string(),
+            host: host.map(str::to_string),
+            contest: contest.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_problem_urls() {
+        let parse = |s: &str| s.parse::<ProblemRef>().unwrap();
+        assert_eq!(
+            parse("https://leetcode.com/problems/two-sum/"),
+            url("two-sum", Some("leetcode.com"), None)
+        );
+        assert_eq!(
+            parse("https://www.leetcode.com/problems/two-sum/description/"),
+            url("two-sum", Some("leetcode.com"), None)
+        );
+        assert_eq!(
+            parse("https://leetcode.cn/problems/two-sum"),
+            url("two-sum", Some("leetcode.cn"), None)
+        );
+        assert_eq!(
+            parse("leetcode.cn/problems/two-sum/"),
+            url("two-sum", Some("leetcode.cn"), None)
+        );
+        assert_eq!(
+            parse("https://leetcode.com/problems/two-sum/?envType=study-plan-v2&envId=top-interview-150"),
+            url("two-sum", Some("leetcode.com"), None)
+        );
+        assert_eq!(
+            parse("https://leetcode.com/problems/two-sum#solution"),
+            url("two-sum", Some("leetcode.com"), None)
+        );
+        assert_eq!(
+            parse("https://leetcode.com/contest/weekly-contest-400/problems/two-sum/"),
+            url("two-sum", Some("leetcode.com"), Some("weekly-contest-400"))
+        );
+        assert_eq!(parse("/problems/two-sum/"), url("two-sum", None, None));
+    }
+
+    #[test]
+    fn rejects_urls_without_a_slug() {
+        assert!("https://leetcode.com/problems/"
+            .parse::<ProblemRef>()
+            .is_err());
+        assert!("https://leetcode.com/problemset/"
+            .parse::<ProblemRef>()
+            .is_err());
+        assert!("  ".parse::<ProblemRef>().is_err());
+    }
+
+    #[test]
+    fn parses_numbers_and_names() {
+        assert_eq!(
+            "1".parse::<ProblemRef>().unwrap(),
+            ProblemRef::Number("1".to_string())
+        );
+        assert_eq!(
+            " two sum ".parse::<ProblemRef>().unwrap(),
+            ProblemRef::Name("two sum".to_string())
+        );
+        assert_eq!(
+            "1two".parse::<ProblemRef>().unwrap(),
+            ProblemRef::Name("1two".to_string())
+        );
+    }
+
+    #[test]
+    fn finds_the_first_valid_url_in_code() {
+        let code = "// see https://example.com/problems/ first\n\
+                    fn main() {}\n\
+                    // #LCEND https://leetcode.cn/problems/two-sum/?envType=daily-question\n";
+        assert_eq!(
+            ProblemRef::find_in(code).unwrap().unwrap(),
+            url("two-sum", Some("leetcode.cn"), None)
+        );
+        assert!(ProblemRef::find_in("https://leetcode.com/problems/")
+            .unwrap()
+            .is_err());
+        assert!(ProblemRef::find_in("fn main() {}").is_none());
+    }
+}
@@ -1,9 +1,9 @@
 use std::fmt;
 
 use colored::Colorize;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Success {
     pub status_code: u8,
     pub lang: String,
@@ -37,7 +37,7 @@ pub struct Success {
     pub state: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CompileError {
     pub compile_error: String,
     pub full_compile_error: String,
@@ -45,7 +45,7 @@ pub struct CompileError {
     pub std_output: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RuntimeError {
     pub runtime_error: String,
     pub full_runtime_error: String,
@@ -53,13 +53,13 @@ pub struct RuntimeError {
     pub std_output: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct WrongTestcase {
     pub invalid_testcase: bool,
     pub runtime_error: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LimitExceeded {
     pub status_code: u8,
     pub lang: String,
@@ -131,14 +131,15 @@ impl fmt::Display for RuntimeError {
         let seperator = "-------------------------------";
         write!(
             f,
-            "{}\nTestcase {} failed during execution!\n{sep}\n{}\n{}\n\n{}\n{}\n{sep}\n{}",
+            "{}\nTestcase {} failed during execution!\n{sep}\n{}\n{}\n\n{}\n{}\n{sep}\n{}\n{:?}",
             "Runtime Error!".red().bold(),
             format!("{}", self.std_output.len()).red(),
             "Error Message :".yellow(),
             self.runtime_error,
             "Full error message :".yellow(),
             self.full_runtime_error,
-            format!("{}\n{:?}", "Std Output :".yellow(), self.std_output),
+            "Std Output :".yellow(),
+            self.std_output,
             sep = seperator.yellow(),
         )
     }
@@ -36,12 +36,7 @@ pub(crate) struct GraphqlRequest {
     pub(crate) variables: String,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct LeetcodeQuestion {
-    pub content: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct DailyChallengeQuestion {
     pub acRate: f64,
@@ -51,7 +46,7 @@ pub struct DailyChallengeQuestion {
     pub titleSlug: String,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Difficulty {
     Easy,
     Medium,
@@ -66,12 +61,15 @@ impl FromStr for Difficulty {
             "easy" => Ok(Difficulty::Easy),
             "medium" => Ok(Difficulty::Medium),
             "hard" => Ok(Difficulty::Hard),
-            _ => Err(eyre::eyre!("Unknown difficulty")),
+            _ => Err(eyre::eyre!(
+                "Unknown difficulty {}, expected easy, medium or hard",
+                s
+            )),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct DailyChallenge {
     pub date: String,
@@ -80,22 +78,34 @@ pub struct DailyChallenge {
     pub question: DailyChallengeQuestion,
 }
 
-#[derive(Debug, Deserialize)]
+/// How the language of the boilerplate code is chosen
+#[derive(Debug, Clone, Copy)]
+pub enum LanguageChoice {
+    /// Fail when no boilerplate code is available in the language
+    Required(Language),
+    /// Fall back to asking when no boilerplate code is available in the language
+    Preferred(Language),
+    Ask,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
-pub(crate) struct BoilerPlateCode {
+pub struct BoilerPlateCode {
     pub(crate) code: String,
     pub(crate) langSlug: String,
 }
 
 use super::super::file_parser::language::Language;
 impl BoilerPlateCode {
-    pub(crate) fn save_code<P: AsRef<Path>>(&self, file_path: P, title_slug: &str) -> Result<()> {
+    /// `problem_link` is appended after the `#LCEND` delimiter
+    /// so that the file can be traced back to its question
+    pub(crate) fn save_code<P: AsRef<Path>>(&self, file_path: P, problem_link: &str) -> Result<()> {
         let language = Language::from_str(&self.langSlug)?;
         let mut file = std::fs::File::create(file_path)?;
         let comment = format!(
-            " {} #LCEND https://leetcode.com/problems/{}/",
+            " {} #LCEND {}",
             language.inline_comment_start(),
-            title_slug.to_lowercase().trim().replace(" ", "-")
+            problem_link
         );
 
         // write code into file along with the comment
@@ -107,9 +117,8 @@ impl BoilerPlateCode {
     pub(crate) fn is_supported(&self) -> bool {
         Language::from_str(&self.langSlug).is_ok()
     }
-    pub(crate) fn extension(&self) -> Result<String> {
-        let language = Language::from_str(&self.langSlug)?;
-        Ok(language.extension().to_owned())
+    pub(crate) fn language(&self) -> Result<Language> {
+        Language::from_str(&self.langSlug)
     }
 }
 
@@ -117,7 +126,7 @@ impl fmt::Display for DailyChallenge {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{title_txt:12}: {}\n{diff:12}: {}\n{date:12}: {}\n{status:12}: {}\n{ac_rate:12}: {:.2}%\n{link:12}: https://leetcode.com{}",
+            "{title_txt:12}: {}\n{diff:12}: {}\n{date:12}: {}\n{status:12}: {}\n{ac_rate:12}: {:.2}%\n{link:12}: {}",
             self.question.title.bright_cyan(),
             Difficulty::from_str(&self.question.difficulty).map_err(|_| fmt::Error)?,
             self.date,
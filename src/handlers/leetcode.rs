@@ -1,66 +1,243 @@
+use std::{
+    sync::OnceLock,
+    time::{Duration, SystemTime},
+};
+
 use super::helpers::*;
+use super::question::*;
 use super::user::*;
 use super::utils::*;
+use crate::cache::ResponseCache;
+use crate::config::CookieSource;
+use crate::cookies::CookieJar;
 use crate::file_parser::codefile::CodeFile;
+use crate::output::{self, AuthError, OfflineError};
 
-use eyre::Result;
+use colored::Colorize;
+use eyre::{bail, Result};
 use serde::Deserialize;
 
 mod api;
 
+pub use api::poll::{PollError, PollOptions};
+
+pub const DEFAULT_BASE_URL: &str = "https://leetcode.com";
+/// Sessions expiring sooner are warned about
+const EXPIRY_WARNING: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+
 pub struct Authorized;
 pub struct Unauthorized;
 
 pub struct LeetCode<State = Unauthorized> {
     state: std::marker::PhantomData<State>,
     client: reqwest::blocking::Client,
+    /// Default headers of `client`, reused by the async client
+    headers: reqwest::header::HeaderMap,
+    base_url: String,
+    polling: PollOptions,
+    cache: Option<ResponseCache>,
+    /// Session read once a question turns out to be locked
+    late_session: Option<LateSession>,
+}
+
+/// Session of a cookie source which is only read when needed, since reading
+/// it may ask for a passphrase or copy the cookie database of a browser
+struct LateSession {
+    source: CookieSource,
+    client: OnceLock<Option<reqwest::blocking::Client>>,
 }
 
 impl LeetCode {
     pub fn new() -> LeetCode<Unauthorized> {
+        let headers =
+            browser_headers(DEFAULT_BASE_URL).expect("default base url is a valid header");
+        let client = reqwest::blocking::Client::builder()
+            .default_headers(headers.clone())
+            .build()
+            .expect("Client::new()");
         LeetCode {
             state: std::marker::PhantomData::<Unauthorized>,
-            client: Default::default(),
+            client,
+            headers,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            polling: PollOptions::default(),
+            cache: None,
+            late_session: None,
         }
     }
 }
 
-impl LeetCode<Unauthorized> {
-    /// # Authenticate with cookie
-    /// Builds a new reqwest client with the cookie
-    pub fn authenticate(&self, cookie: &str) -> Result<LeetCode<Authorized>> {
-        let mut headers = reqwest::header::HeaderMap::with_capacity(5);
-        let csrf_token = cookie
-            .split(';')
-            .find(|s| s.contains("csrftoken"))
-            .map(|s| s.split('=').last())
-            .flatten()
-            .ok_or_else(|| eyre::eyre!("No csrf token found"))?;
+impl<State> LeetCode<State> {
+    /// Resolves `path` against the configured base URL
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
 
+    /// Host name of the instance, e.g. `leetcode.com`
+    pub(crate) fn host(&self) -> String {
+        reqwest::Url::parse(&self.base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    /// Link to the problem page of `title_slug`
+    pub(crate) fn problem_url(&self, title_slug: &str) -> String {
+        self.url(&format!("problems/{}/", title_slug))
+    }
+
+    /// Client with the session of the late session source, read on first use.
+    /// `None` without such a source or when it can't be read
+    pub(crate) fn late_session_client(&self) -> Option<&reqwest::blocking::Client> {
+        let late = self.late_session.as_ref()?;
+        late.client
+            .get_or_init(|| {
+                let host = self.host();
+                let session = late
+                    .source
+                    .read(&host)
+                    .and_then(|cookie| CookieJar::parse(&cookie, &host))
+                    .and_then(|jar| self.session_client(&jar));
+                match session {
+                    Ok((_, client)) => Some(client),
+                    Err(err) => {
+                        output::info(format!(
+                            "Failed to read the session from {} : {}",
+                            late.source, err
+                        ));
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Headers and client sending the cookies of `jar`, warns when the
+    /// session is about to expire
+    fn session_client(
+        &self,
+        jar: &CookieJar,
+    ) -> Result<(reqwest::header::HeaderMap, reqwest::blocking::Client)> {
+        jar.require_session()?;
+        if jar.is_expired() {
+            bail!(AuthError(
+                "The LeetCode session has expired, renew the cookie".to_string()
+            ));
+        }
+        if let Some(left) = jar
+            .session_expiry()
+            .and_then(|expiry| expiry.duration_since(SystemTime::now()).ok())
+            .filter(|left| *left < EXPIRY_WARNING)
+        {
+            output::info(
+                format!(
+                    "The LeetCode session expires in {}, renew the cookie soon",
+                    days_and_hours(left)
+                )
+                .yellow(),
+            );
+        }
+
+        let mut headers = self.headers.clone();
+        let csrf_token = jar.csrf_token().unwrap_or_default();
         headers.insert(
             reqwest::header::COOKIE,
-            reqwest::header::HeaderValue::from_str(&cookie)?,
-        );
-        headers.insert(
-                reqwest::header::USER_AGENT,
-                reqwest::header::HeaderValue::from_str("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/110.0.0.0 Safari/537.36")?,
-        );
-        headers.insert(
-            reqwest::header::REFERER,
-            reqwest::header::HeaderValue::from_str("https://leetcode.com/")?,
+            reqwest::header::HeaderValue::from_str(&jar.header())
+                .map_err(|_| AuthError("Cookie contains invalid characters".to_string()))?,
         );
         headers.insert(
             reqwest::header::HeaderName::from_static("x-csrftoken"),
-            reqwest::header::HeaderValue::from_str(csrf_token)?,
+            reqwest::header::HeaderValue::from_str(csrf_token)
+                .map_err(|_| AuthError("Cookie contains invalid characters".to_string()))?,
         );
         let client = reqwest::blocking::Client::builder()
             .default_headers(headers.clone())
             .build()?;
+        Ok((headers, client))
+    }
+
+    /// Fails when `--offline`, for requests which can't be served from the cache,
+    /// `what` completes "Can't ..."
+    pub(crate) fn ensure_online(&self, what: &str) -> Result<()> {
+        if self.cache.as_ref().is_some_and(ResponseCache::is_offline) {
+            eyre::bail!(OfflineError(format!("Can't {} while --offline", what)));
+        }
+        Ok(())
+    }
+}
+
+impl LeetCode<Unauthorized> {
+    /// # Change the LeetCode instance
+    /// Points the client at another LeetCode instance,
+    /// like `https://leetcode.cn` or a local mock server
+    pub fn with_base_url(mut self, base_url: &str) -> Result<Self> {
+        let url = reqwest::Url::parse(base_url)
+            .map_err(|err| eyre::eyre!("Invalid base url {}: {}", base_url, err))?;
+        if url.cannot_be_a_base() {
+            eyre::bail!("Invalid base url {}: expected an http(s) url", base_url);
+        }
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self.headers = browser_headers(&self.url(""))?;
+        self.client = reqwest::blocking::Client::builder()
+            .default_headers(self.headers.clone())
+            .build()?;
+        Ok(self)
+    }
+
+    /// # Change how results are polled
+    /// Sets the interval and deadline of the checks for run and submission results
+    pub fn with_polling(mut self, polling: PollOptions) -> Self {
+        self.polling = polling;
+        self
+    }
+
+    /// # Cache question data
+    /// Serves question data from the on-disk cache while it's fresh
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// # Read the session when needed
+    /// Fetches locked questions again with the session of `source`,
+    /// which is only read then
+    pub fn with_late_session(mut self, source: CookieSource) -> Self {
+        self.late_session = Some(LateSession {
+            source,
+            client: OnceLock::new(),
+        });
+        self
+    }
+
+    /// # Authenticate with cookie
+    /// Builds a new reqwest client with the cookies, warns when the session
+    /// is about to expire
+    pub fn authenticate(&self, jar: &CookieJar) -> Result<LeetCode<Authorized>> {
+        let (headers, client) = self.session_client(jar)?;
         Ok(LeetCode {
             state: std::marker::PhantomData::<Authorized>,
             client,
+            headers,
+            base_url: self.base_url.clone(),
+            polling: self.polling,
+            cache: self.cache.clone(),
+            late_session: None,
         })
     }
 }
 
 impl LeetCode<Authorized> {}
+
+/// Headers making requests look like they come from a browser on `base_url`
+fn browser_headers(base_url: &str) -> Result<reqwest::header::HeaderMap> {
+    let mut headers = reqwest::header::HeaderMap::with_capacity(5);
+    headers.insert(
+        reqwest::header::USER_AGENT,
+        reqwest::header::HeaderValue::from_static("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/110.0.0.0 Safari/537.36"),
+    );
+    headers.insert(
+        reqwest::header::REFERER,
+        reqwest::header::HeaderValue::from_str(base_url)?,
+    );
+    Ok(headers)
+}
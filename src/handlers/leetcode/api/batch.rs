@@ -0,0 +1,73 @@
+//! Async client, for fetching many questions at once
+
+use super::question::*;
+use crate::cache::{Lookup, ResponseCache};
+use crate::handlers::leetcode::*;
+
+use eyre::{Context, Result};
+use futures::{stream, StreamExt};
+
+/// Async variant of [`LeetCode`], sharing its session if any
+pub struct AsyncLeetCode {
+    client: reqwest::Client,
+    base_url: String,
+    cache: Option<ResponseCache>,
+}
+
+impl<State> LeetCode<State> {
+    /// Builds an async client with the same headers and base URL
+    pub fn to_async(&self) -> Result<AsyncLeetCode> {
+        let client = reqwest::Client::builder()
+            .default_headers(self.headers.clone())
+            .build()?;
+        Ok(AsyncLeetCode {
+            client,
+            base_url: self.base_url.clone(),
+            cache: self.cache.clone(),
+        })
+    }
+}
+
+impl AsyncLeetCode {
+    /// Fetches everything about a question in a single request,
+    /// served from the cache while it's fresh
+    pub async fn question_detail(&self, title_slug: &str) -> Result<QuestionDetail> {
+        let stale = match cached_detail(self.cache.as_ref(), title_slug)? {
+            Lookup::Hit(body) => return parse_detail(&body, title_slug),
+            Lookup::Stale(body) => Some(body),
+            Lookup::Miss => None,
+        };
+        let fetched = self.fetch_detail(title_slug).await;
+        fetched_detail(self.cache.as_ref(), title_slug, fetched, stale)
+    }
+
+    async fn fetch_detail(&self, title_slug: &str) -> Result<String> {
+        let body = self
+            .client
+            .post(format!("{}/graphql", self.base_url))
+            .json(&question_query(QUESTION_DETAIL_QUERY, title_slug)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+            .wrap_err("Failed to fetch question from LeetCode")?;
+        Ok(body)
+    }
+
+    /// Fetches many questions with at most `concurrency` of them in flight,
+    /// results are in order of completion
+    pub async fn fetch_questions(
+        &self,
+        title_slugs: &[String],
+        concurrency: usize,
+    ) -> Vec<(String, Result<QuestionDetail>)> {
+        stream::iter(title_slugs)
+            .map(|title_slug| async move {
+                (title_slug.clone(), self.question_detail(title_slug).await)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await
+    }
+}
@@ -1,29 +1,23 @@
 use crate::handlers::{leetcode::*, utils::ExecutionResult};
+use crate::output;
 
-use eyre::{bail, Context, Result};
+use eyre::{Context, Result};
 
 impl LeetCode<Authorized> {
-    pub fn execute_default(&self, codefile: &CodeFile) -> Result<ExecutionResult> {
-        self.execute(codefile, String::new())
-    }
-    pub fn execute(&self, codefile: &CodeFile, mut data_input: String) -> Result<ExecutionResult> {
-        let question_title = codefile.question_title.clone();
-        let ques = self.question_metadata(&question_title)?;
+    pub fn execute(
+        &self,
+        codefile: &CodeFile,
+        question: &QuestionDetail,
+        mut data_input: String,
+    ) -> Result<ExecutionResult> {
         if data_input.is_empty() {
-            data_input = ques.exampleTestcaseList.join("\n");
-
-            // write this to testcase.txt
-            let mut file = std::fs::File::create("testcase.txt")?;
-            std::io::Write::write_all(&mut file, data_input.as_bytes())?;
-            println!("Wrote default testcases to testcase.txt");
+            data_input = question.exampleTestcaseList.join("\n");
         }
 
-        let question_id = ques.questionId;
-
         self._execute(
             codefile.language.to_string(),
-            question_id,
-            question_title,
+            question.questionId.clone(),
+            codefile.question_title.clone(),
             codefile.code.clone(),
             data_input,
         )
@@ -37,11 +31,9 @@ impl LeetCode<Authorized> {
         typed_code: String,
         data_input: String,
     ) -> Result<ExecutionResult> {
+        self.ensure_online("run testcases on LeetCode, use --local")?;
         let client = &self.client;
-        let url = format!(
-            "https://leetcode.com/problems/{}/interpret_solution/",
-            question_title
-        );
+        let url = self.url(&format!("problems/{}/interpret_solution/", question_title));
 
         let testcase = TestCaseExec {
             lang,
@@ -62,47 +54,12 @@ impl LeetCode<Authorized> {
 
         let interpret_id = data.interpret_id;
 
-        println!("Executing testcases...");
-        let mut last_state = PendingState::Unknown;
-        loop {
-            let url = format!("https://leetcode.com/submissions/detail/{interpret_id}/check/");
-            // std::thread::sleep(std::time::Duration::from_secs(7));
-            let data = client
-                .get(&url)
-                .send()?
-                .json::<ExecutionResult>()
-                .wrap_err(
-                "Failed to parse JSON from LeetCode, Try again after sometime or renew your cookie",
-            )?;
+        output::queued("executing", &interpret_id, "Executing testcases...");
+        self.poll(&interpret_id)
+    }
 
-            match data {
-                ExecutionResult::PendingResult(data) => {
-                    let curr_state = data.state();
-                    match curr_state {
-                        PendingState::Pending => {
-                            if last_state != PendingState::Pending {
-                                println!("Status : Evaluation Pending");
-                            }
-                        }
-                        PendingState::Started => {
-                            if last_state != PendingState::Started {
-                                println!("Status : Execution Started");
-                            }
-                        }
-                        PendingState::Success => {
-                            bail!("our code was executed successfully but we failed to parse result\nCheck on leetcode manually");
-                        }
-                        PendingState::Unknown => {
-                            bail!(
-                                "Status : {}\nKindly report this state to developer",
-                                data.state.as_str()
-                            );
-                        }
-                    };
-                    last_state = curr_state;
-                }
-                not_pending_data => return Ok(not_pending_data),
-            };
-        }
+    /// Fetches the result of a run, waiting while it's pending
+    pub fn check_run(&self, interpret_id: &str) -> Result<ExecutionResult> {
+        self.poll(interpret_id)
     }
 }
@@ -1,3 +1,6 @@
+pub mod batch;
 pub mod execute;
+pub mod poll;
+pub mod problems;
 pub mod question;
 pub mod submit;
@@ -0,0 +1,253 @@
+use crate::handlers::{leetcode::*, utils::ExecutionResult};
+use crate::output;
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+    time::{Duration, Instant},
+};
+
+use eyre::{bail, Context, Result};
+use reqwest::{header::RETRY_AFTER, StatusCode};
+use serde::de::DeserializeOwned;
+
+/// Set by Ctrl-C while a check is being polled
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+static POLLING: AtomicBool = AtomicBool::new(false);
+static HANDLER: Once = Once::new();
+
+const BACKOFF_FACTOR: f64 = 1.5;
+const MAX_INTERVAL: Duration = Duration::from_secs(10);
+/// Shortest delay between checks, a zero `poll_interval` would never back off
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+/// Granularity of the sleeps, so that Ctrl-C is noticed quickly
+const SLEEP_STEP: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy)]
+pub struct PollOptions {
+    /// Delay between the first checks, grows exponentially up to 10 seconds
+    pub interval: Duration,
+    /// Overall deadline of the polling
+    pub timeout: Duration,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Polling stopped before LeetCode returned a verdict
+#[derive(Debug)]
+pub enum PollError {
+    Cancelled { id: String },
+    TimedOut { id: String, after: Duration },
+}
+
+impl PollError {
+    /// Id of the run or submission whose result is still pending
+    pub fn id(&self) -> &str {
+        match self {
+            PollError::Cancelled { id } | PollError::TimedOut { id, .. } => id,
+        }
+    }
+}
+
+impl fmt::Display for PollError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PollError::Cancelled { id } => write!(f, "Cancelled while waiting for {}", id),
+            PollError::TimedOut { id, after } => {
+                write!(f, "No result for {} after {} seconds", id, after.as_secs())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PollError {}
+
+/// Response of the `/check/` endpoint, pending until LeetCode has a verdict
+pub(crate) trait CheckResult: DeserializeOwned {
+    fn pending(&self) -> Option<&PendingResult>;
+}
+
+impl CheckResult for ExecutionResult {
+    fn pending(&self) -> Option<&PendingResult> {
+        match self {
+            ExecutionResult::PendingResult(pending) => Some(pending),
+            _ => None,
+        }
+    }
+}
+
+impl CheckResult for SubmissionResult {
+    fn pending(&self) -> Option<&PendingResult> {
+        match self {
+            SubmissionResult::PendingResult(pending) => Some(pending),
+            _ => None,
+        }
+    }
+}
+
+impl LeetCode<Authorized> {
+    /// Polls the result of a run or submission until it's no longer pending,
+    /// backing off between checks and retrying on rate limits and server errors
+    pub(crate) fn poll<T: CheckResult>(&self, id: &str) -> Result<T> {
+        self.ensure_online("check results")?;
+        install_handler();
+        CANCELLED.store(false, Ordering::SeqCst);
+        POLLING.store(true, Ordering::SeqCst);
+        let result = self.poll_until_done(id);
+        POLLING.store(false, Ordering::SeqCst);
+        result
+    }
+
+    fn poll_until_done<T: CheckResult>(&self, id: &str) -> Result<T> {
+        let url = self.url(&format!("submissions/detail/{id}/check/"));
+        let started = Instant::now();
+        // a timeout too long to be represented never expires
+        let deadline = started.checked_add(self.polling.timeout);
+        let mut interval = self.polling.interval.max(MIN_INTERVAL);
+        let mut last_state = PendingState::Unknown;
+
+        loop {
+            let mut delay = interval;
+            match self.client.get(&url).send() {
+                Ok(response)
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS
+                        || response.status().is_server_error() =>
+                {
+                    if let Some(retry_after) = retry_after(&response) {
+                        delay = retry_after;
+                    }
+                    output::status(
+                        "retrying",
+                        format!(
+                            "Status : LeetCode responded {}, retrying",
+                            response.status()
+                        ),
+                    );
+                }
+                Ok(response) => {
+                    let data = response.json::<T>().wrap_err(
+                        "Failed to parse JSON from LeetCode, Try again after sometime or renew your cookie",
+                    )?;
+                    let Some(pending) = data.pending() else {
+                        return Ok(data);
+                    };
+                    let curr_state = pending.state();
+                    match curr_state {
+                        PendingState::Pending if last_state != PendingState::Pending => {
+                            output::status("pending", "Status : Evaluation Pending");
+                        }
+                        PendingState::Started if last_state != PendingState::Started => {
+                            output::status("started", "Status : Execution Started");
+                        }
+                        PendingState::Success => {
+                            bail!("Your code was executed successfully but we failed to parse result\nCheck on leetcode manually");
+                        }
+                        PendingState::Unknown => {
+                            bail!(
+                                "Status : {}\nKindly report this state to developer",
+                                pending.state
+                            );
+                        }
+                        _ => {}
+                    };
+                    last_state = curr_state;
+                }
+                // the connection dropped, the result is still kept by LeetCode
+                Err(err) if err.is_timeout() || err.is_connect() => {
+                    output::status("retrying", format!("Status : {}, retrying", err));
+                }
+                Err(err) => return Err(err.into()),
+            }
+
+            let now = Instant::now();
+            if deadline.is_some_and(|deadline| now >= deadline) {
+                bail!(PollError::TimedOut {
+                    id: id.to_string(),
+                    after: now - started,
+                });
+            }
+            sleep(
+                deadline.map_or(delay, |deadline| delay.min(deadline - now)),
+                id,
+            )?;
+            interval = next_interval(interval);
+        }
+    }
+}
+
+/// Backs off up to `MAX_INTERVAL`, intervals configured longer than it stay
+/// as they are and aren't multiplied, which could overflow
+fn next_interval(interval: Duration) -> Duration {
+    if interval >= MAX_INTERVAL {
+        interval
+    } else {
+        interval.mul_f64(BACKOFF_FACTOR).min(MAX_INTERVAL)
+    }
+}
+
+/// Ctrl-C cancels the polling, and exits as usual otherwise
+fn install_handler() {
+    HANDLER.call_once(|| {
+        let _ = ctrlc::set_handler(|| {
+            if POLLING.load(Ordering::SeqCst) {
+                CANCELLED.store(true, Ordering::SeqCst);
+            } else {
+                std::process::exit(130);
+            }
+        });
+    });
+}
+
+fn sleep(duration: Duration, id: &str) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        if CANCELLED.load(Ordering::SeqCst) {
+            bail!(PollError::Cancelled { id: id.to_string() });
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= duration {
+            return Ok(());
+        }
+        std::thread::sleep(SLEEP_STEP.min(duration - elapsed));
+    }
+}
+
+/// Delay requested by a `Retry-After` header in seconds
+fn retry_after(response: &reqwest::blocking::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_up_to_the_max_interval() {
+        assert_eq!(next_interval(MIN_INTERVAL), Duration::from_millis(375));
+        assert_eq!(next_interval(Duration::from_secs(8)), MAX_INTERVAL);
+        assert_eq!(next_interval(MAX_INTERVAL), MAX_INTERVAL);
+        assert_eq!(
+            next_interval(Duration::from_secs(u64::MAX)),
+            Duration::from_secs(u64::MAX)
+        );
+        assert_eq!(next_interval(Duration::MAX), Duration::MAX);
+    }
+}
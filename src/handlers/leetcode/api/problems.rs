@@ -0,0 +1,130 @@
+use crate::cache::Lookup;
+use crate::handlers::leetcode::*;
+use crate::handlers::problems::ProblemSummary;
+use crate::output;
+
+use eyre::{Context, Result};
+
+const PROBLEM_LIST_QUERY: &str = r#"
+    query problemsetQuestionList($categorySlug: String, $limit: Int, $skip: Int, $filters: QuestionListFilterInput) {
+        problemsetQuestionList: questionList(categorySlug: $categorySlug, limit: $limit, skip: $skip, filters: $filters) {
+            questions: data {
+                frontendQuestionId: questionFrontendId
+                title
+                titleSlug
+                difficulty
+                acRate
+                paidOnly: isPaidOnly
+                status
+                topicTags {
+                    name
+                    slug
+                }
+            }
+        }
+    }
+"#;
+
+/// Cache directory of the problem set, kept under a single key
+const PROBLEM_LIST_CACHE: &str = "problemsetQuestionList";
+const PROBLEM_LIST_KEY: &str = "all";
+/// More than the problem set holds, so that it comes in a single page
+const PROBLEM_LIST_LIMIT: u32 = 10_000;
+
+#[derive(Deserialize)]
+struct ProblemListData {
+    data: ProblemListField,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct ProblemListField {
+    problemsetQuestionList: ProblemListPage,
+}
+
+#[derive(Deserialize)]
+struct ProblemListPage {
+    questions: Vec<ProblemSummary>,
+}
+
+fn parse_problem_list(body: &str) -> Result<Vec<ProblemSummary>> {
+    Ok(serde_json::from_str::<ProblemListData>(body)
+        .wrap_err("Failed to parse the problem list from LeetCode")?
+        .data
+        .problemsetQuestionList
+        .questions)
+}
+
+/// The statuses are those of the session, keeps them out of the cache shared
+/// by every session of the host
+fn without_statuses(body: &str) -> Result<String> {
+    let mut body: serde_json::Value =
+        serde_json::from_str(body).wrap_err("Failed to parse the problem list from LeetCode")?;
+    if let Some(questions) = body
+        .pointer_mut("/data/problemsetQuestionList/questions")
+        .and_then(serde_json::Value::as_array_mut)
+    {
+        for question in questions
+            .iter_mut()
+            .filter_map(serde_json::Value::as_object_mut)
+        {
+            question.remove("status");
+        }
+    }
+    Ok(body.to_string())
+}
+
+impl<State> LeetCode<State> {
+    /// Every question of the problem set, served from the cache while it's
+    /// fresh. The cache holds no statuses, `with_status` always fetches them
+    pub fn problem_list(&self, with_status: bool) -> Result<Vec<ProblemSummary>> {
+        if with_status {
+            self.ensure_online("read the problem statuses")?;
+        }
+        let lookup = match &self.cache {
+            Some(cache) if !with_status => cache.lookup(PROBLEM_LIST_CACHE, PROBLEM_LIST_KEY)?,
+            _ => Lookup::Miss,
+        };
+        let stale = match lookup {
+            Lookup::Hit(body) => return parse_problem_list(&body),
+            Lookup::Stale(body) => Some(body),
+            Lookup::Miss => None,
+        };
+
+        let variables = serde_json::json!({
+            "categorySlug": "",
+            "skip": 0,
+            "limit": PROBLEM_LIST_LIMIT,
+            "filters": {},
+        });
+        let fetched = self
+            .client
+            .post(self.url("graphql"))
+            .json(&GraphqlRequest {
+                query: PROBLEM_LIST_QUERY.to_string(),
+                variables: variables.to_string(),
+            })
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .and_then(reqwest::blocking::Response::text)
+            .wrap_err("Failed to fetch the problem list from LeetCode");
+        match (fetched, stale) {
+            (Ok(body), _) => {
+                let problems = parse_problem_list(&body)?;
+                if let Some(cache) = &self.cache {
+                    cache.store(
+                        PROBLEM_LIST_CACHE,
+                        PROBLEM_LIST_KEY,
+                        &without_statuses(&body)?,
+                    );
+                }
+                Ok(problems)
+            }
+            (Err(err), Some(stale)) if output::is_network_error(&err) => {
+                output::info("LeetCode couldn't be reached, using the cached problem list");
+                parse_problem_list(&stale)
+            }
+            (Err(err), _) => Err(err),
+        }
+    }
+}
@@ -1,12 +1,122 @@
+use crate::cache::{Lookup, ResponseCache};
+use crate::config::{render_template, DEFAULT_FILENAME};
 use crate::handlers::leetcode::*;
+use crate::output::{self, AuthError};
+
+use std::io::IsTerminal;
 
 use colored::Colorize;
 use eyre::{bail, Context, Result};
 
-const GRAPHQL_URL: &str = "https://leetcode.com/graphql";
+pub(super) const QUESTION_DETAIL_QUERY: &str = r#"
+    query questionDetail($titleSlug: String!) {
+        question(titleSlug: $titleSlug) {
+            questionId
+            questionFrontendId
+            title
+            titleSlug
+            difficulty
+            isPaidOnly
+            topicTags {
+                name
+                slug
+            }
+            content
+            mysqlSchemas
+            codeSnippets {
+                lang
+                langSlug
+                code
+            }
+            exampleTestcaseList
+            metaData
+            hints
+            similarQuestions
+            stats
+        }
+    }
+"#;
 
-impl LeetCode<Authorized> {
+/// Cache directory of the responses to `QUESTION_DETAIL_QUERY`
+const QUESTION_DETAIL_CACHE: &str = "questionDetail";
+
+/// Request of a question query
+pub(super) fn question_query(query: &str, title_slug: &str) -> Result<GraphqlRequest> {
+    Ok(GraphqlRequest {
+        query: query.to_string(),
+        variables: serde_json::to_string(&Variables {
+            titleSlug: title_slug.to_string(),
+        })?,
+    })
+}
+
+/// Response of a query on `question(titleSlug: ...)`
+#[derive(Deserialize)]
+pub(super) struct QuestionData<T> {
+    pub(super) data: QuestionField<T>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct QuestionField<T> {
+    pub(super) question: T,
+}
+
+/// Cached response to the detail query of `title_slug`. A locked question
+/// is fetched again unless offline, the session may have premium by now
+pub(super) fn cached_detail(cache: Option<&ResponseCache>, title_slug: &str) -> Result<Lookup> {
+    let Some(cache) = cache else {
+        return Ok(Lookup::Miss);
+    };
+    match cache.lookup(QUESTION_DETAIL_CACHE, title_slug)? {
+        Lookup::Hit(body)
+            if !cache.is_offline()
+                && parse_detail(&body, title_slug).is_ok_and(|question| question.is_locked()) =>
+        {
+            Ok(Lookup::Stale(body))
+        }
+        lookup => Ok(lookup),
+    }
+}
+
+/// Parses and caches a freshly fetched detail query, unless the question is
+/// locked since the cache is shared by sessions with and without premium.
+/// Falls back to the stale response when LeetCode couldn't be reached
+pub(super) fn fetched_detail(
+    cache: Option<&ResponseCache>,
+    title_slug: &str,
+    fetched: Result<String>,
+    stale: Option<String>,
+) -> Result<QuestionDetail> {
+    match (fetched, stale) {
+        (Ok(body), _) => {
+            let question = parse_detail(&body, title_slug)?;
+            if let Some(cache) = cache.filter(|_| !question.is_locked()) {
+                cache.store(QUESTION_DETAIL_CACHE, title_slug, &body);
+            }
+            Ok(question)
+        }
+        (Err(err), Some(stale)) if output::is_network_error(&err) => {
+            output::info(format!(
+                "LeetCode couldn't be reached, using the cached {}",
+                title_slug
+            ));
+            parse_detail(&stale, title_slug)
+        }
+        (Err(err), _) => Err(err),
+    }
+}
+
+pub(super) fn parse_detail(body: &str, title_slug: &str) -> Result<QuestionDetail> {
+    serde_json::from_str::<QuestionData<Option<QuestionDetail>>>(body)
+        .wrap_err("Failed to parse question from LeetCode")?
+        .data
+        .question
+        .ok_or_else(|| eyre::eyre!("No question named {}", title_slug))
+}
+
+impl<State> LeetCode<State> {
     pub fn get_daily_challenge(&self) -> Result<DailyChallenge> {
+        self.ensure_online("fetch the daily challenge")?;
         let client = &self.client;
         let query = GraphqlRequest {
             query: r#"
@@ -40,7 +150,7 @@ impl LeetCode<Authorized> {
             variables: "{}".to_string(),
         };
 
-        let data = client.post(GRAPHQL_URL).json(&query).send()?;
+        let data = client.post(self.url("graphql")).json(&query).send()?;
 
         // println!("{:?}", data.text());
         // todo!();
@@ -55,184 +165,167 @@ impl LeetCode<Authorized> {
             data: DailyChallengeWrapper,
         }
 
-        Ok(data
+        let mut daily_challenge = data
             .json::<Wrapper>()?
             .data
-            .activeDailyCodingChallengeQuestion)
+            .activeDailyCodingChallengeQuestion;
+        daily_challenge.link = self.url(&daily_challenge.link);
+        Ok(daily_challenge)
     }
 
-    pub fn get_metadata(&self) -> Result<UserMetadata> {
-        let client = &self.client;
-        let data = client
-            .get("https://leetcode.com/api/problems/all/")
-            .send()
-            .wrap_err("Failed to fetch metadata from LeetCode")?;
-
-        let metadata = data
-            .json::<UserMetadata>()
-            .wrap_err("Failed to parse metadata, Try renewing cookie")?;
-        if metadata.user_name.is_empty() {
-            bail!("Cookie invalid. Renew cookies");
-        }
-        Ok(metadata)
-    }
-
-    pub fn question_content(&self, title_slug: &str) -> Result<LeetcodeQuestion> {
-        let client = &self.client;
-        let query = GraphqlRequest {
-            query: r#"
-            query questionContent($titleSlug: String!) {
-                question(titleSlug: $titleSlug) {
-                    content mysqlSchemas
-                }
-            }
-            "#
-            .to_string(),
-            variables: serde_json::to_string(&Variables {
-                titleSlug: title_slug.to_string(),
-            })?,
+    /// Fetches everything about a question in a single request,
+    /// served from the cache while it's fresh. A locked question is
+    /// fetched again with the late session, if any
+    pub fn question_detail(&self, title_slug: &str) -> Result<QuestionDetail> {
+        let stale = match cached_detail(self.cache.as_ref(), title_slug)? {
+            Lookup::Hit(body) => return parse_detail(&body, title_slug),
+            Lookup::Stale(body) => Some(body),
+            Lookup::Miss => None,
         };
-
-        let data = client.post(GRAPHQL_URL).json(&query).send()?;
-
-        #[derive(Deserialize)]
-        struct QuestionWrapper {
-            question: LeetcodeQuestion,
+        let fetched = self.fetch_detail(&self.client, title_slug);
+        let question = fetched_detail(self.cache.as_ref(), title_slug, fetched, stale)?;
+        if !question.is_locked() {
+            return Ok(question);
         }
-        #[derive(Deserialize)]
-        struct Data {
-            data: QuestionWrapper,
+        match self.late_session_client() {
+            Some(client) => {
+                let fetched = self.fetch_detail(client, title_slug);
+                fetched_detail(self.cache.as_ref(), title_slug, fetched, None)
+            }
+            None => Ok(question),
         }
+    }
 
-        Ok(data.json::<Data>().map(|op| op.data.question)?)
+    fn fetch_detail(&self, client: &reqwest::blocking::Client, title_slug: &str) -> Result<String> {
+        client
+            .post(self.url("graphql"))
+            .json(&question_query(QUESTION_DETAIL_QUERY, title_slug)?)
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .and_then(reqwest::blocking::Response::text)
+            .wrap_err("Failed to fetch question from LeetCode")
     }
-    pub fn save_boiler_code(&self, title_slug: &str) -> Result<()> {
-        let client = &self.client;
-        let query = r#"
-            query questionEditorData($titleSlug: String!) {
-                question(titleSlug: $titleSlug) {
-                    questionId
-                        questionFrontendId
-                        codeSnippets {
-                            lang
-                                langSlug
-                                code
-                        }
-                    envInfo
-                        enableRunCode
-                }
-            }
-        "#;
-        let variables = serde_json::to_string(&Variables {
-            titleSlug: title_slug.to_string(),
-        })?;
-        let boiler_code = client
-            .post(GRAPHQL_URL)
-            .json(&GraphqlRequest {
-                query: query.to_string(),
-                variables,
-            })
-            .send()?;
-
-        #[derive(Debug, Deserialize)]
-        #[allow(non_snake_case)]
-        struct CodeSnippets {
-            codeSnippets: Vec<BoilerPlateCode>,
-        }
-        #[derive(Debug, Deserialize)]
-        struct WrapperData {
-            question: CodeSnippets,
-        }
-        #[derive(Debug, Deserialize)]
-        struct Wrapper {
-            data: WrapperData,
-        }
-        let boiler_code_vector = boiler_code.json::<Wrapper>()?.data.question.codeSnippets;
 
-        let mut boiler_code_vector = boiler_code_vector
-            .into_iter()
-            .filter(|code| code.is_supported())
-            .collect::<Vec<_>>();
+    /// Saves the boilerplate code of the question, the language and filename
+    /// are asked interactively when not specified and stdin is a terminal
+    pub fn save_boiler_code(
+        &self,
+        question: &QuestionDetail,
+        choice: LanguageChoice,
+        filename_template: Option<&str>,
+    ) -> Result<()> {
+        let mut boiler_code_vector = question.snippets().collect::<Vec<_>>();
+
+        let language = match choice {
+            LanguageChoice::Required(language) | LanguageChoice::Preferred(language) => {
+                Some(language)
+            }
+            LanguageChoice::Ask => None,
+        };
+        let preferred = language.and_then(|language| {
+            boiler_code_vector
+                .iter()
+                .position(|code| code.language().ok() == Some(language))
+        });
+        let available = || {
+            boiler_code_vector
+                .iter()
+                .map(|code| code.langSlug.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let interactive = std::io::stdin().is_terminal();
 
         // ask user to specify language among these options
-        let boiler_code = match boiler_code_vector.len() {
-            0 => bail!("No boiler plate code available in supported language!"),
-            1 => boiler_code_vector.swap_remove(0),
+        let boiler_code = match (preferred, boiler_code_vector.len()) {
+            (Some(idx), _) => boiler_code_vector.swap_remove(idx),
+            (None, 0) => bail!("No boiler plate code available in supported language!"),
+            (None, _) if matches!(choice, LanguageChoice::Required(_)) => bail!(
+                "No boiler plate code available in {}, available languages : {}",
+                language.expect("Required choice has a language"),
+                available()
+            ),
+            (None, 1) => boiler_code_vector.swap_remove(0),
+            (None, _) if !interactive => bail!(
+                "Multiple languages available : {}\nSpecify one with --lang",
+                available()
+            ),
             _ => {
+                if let Some(language) = language {
+                    output::info(format!("No boiler plate code available in {}", language));
+                }
                 let mut input = String::new();
-                println!(
-                    "{}",
-                    "\nPlease select a language from the following options :".yellow()
-                );
+                // prompts go through output::info so that stdout only holds JSON
+                output::info("\nPlease select a language from the following options :".yellow());
                 for (i, code) in boiler_code_vector.iter().enumerate() {
-                    println!("{}: {}", i, code.langSlug);
+                    output::info(format!("{}: {}", i, code.langSlug));
                 }
-                println!(
+                output::info(format!(
                     "\nFor example : Input \"{}\" for {}",
                     "0".cyan(),
                     &boiler_code_vector[0].langSlug.cyan()
-                );
+                ));
                 std::io::stdin().read_line(&mut input)?;
                 let input = input.trim().parse::<usize>()?;
+                if input >= boiler_code_vector.len() {
+                    bail!("Invalid option {}", input);
+                }
                 boiler_code_vector.swap_remove(input)
             }
         };
 
-        let mut input = String::new();
-        println!("Filename (main.{}) : ", &(boiler_code.extension()?));
-        std::io::stdin().read_line(&mut input)?;
-        let input = input.trim();
-        let filename = if input.is_empty() {
-            format!("main.{}", boiler_code.extension()?)
-        } else {
-            input.to_string()
+        let title_slug = &question.titleSlug;
+        let filename = match filename_template {
+            Some(template) => render_template(template, title_slug, boiler_code.language()?),
+            None if !interactive => {
+                render_template(DEFAULT_FILENAME, title_slug, boiler_code.language()?)
+            }
+            None => {
+                let default_filename =
+                    render_template(DEFAULT_FILENAME, title_slug, boiler_code.language()?);
+                let mut input = String::new();
+                output::info(format!("Filename ({}) : ", default_filename));
+                std::io::stdin().read_line(&mut input)?;
+                let input = input.trim();
+                if input.is_empty() {
+                    default_filename
+                } else {
+                    input.to_string()
+                }
+            }
         };
-        boiler_code.save_code(&filename, &title_slug)?;
+        if let Some(parent) = std::path::Path::new(&filename).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        boiler_code.save_code(&filename, &self.problem_url(title_slug))?;
+        if !boiler_code.language()?.is_database() {
+            return Ok(());
+        }
+        if let Some(path) = question.save_schema(std::path::Path::new(&filename))? {
+            output::info(format!(
+                "Saved the schema of the tables to {}",
+                path.display().to_string().cyan()
+            ));
+        }
         Ok(())
     }
+}
 
-    pub fn question_metadata(&self, title_slug: &str) -> Result<Question> {
+impl LeetCode<Authorized> {
+    pub fn get_metadata(&self) -> Result<UserMetadata> {
+        self.ensure_online("authenticate")?;
         let client = &self.client;
-
-        let query = GraphqlRequest {
-            query: r#"
-            query consolePanelConfig($titleSlug: String!) {
-                question(titleSlug: $titleSlug) {
-                    questionId
-                    questionFrontendId
-                    questionTitle
-                    enableDebugger
-                    enableRunCode
-                    enableSubmit
-                    enableTestMode
-                    exampleTestcaseList
-                    metaData
-                }
-            }
-            "#
-            .to_string(),
-            variables: serde_json::to_string(&Variables {
-                titleSlug: title_slug.to_string(),
-            })?,
-        };
         let data = client
-            .post(GRAPHQL_URL)
-            .json(&query)
+            .get(self.url("api/problems/all/"))
             .send()
-            .wrap_err("Failed to fetch question id from LeetCode")?;
-
-        #[derive(Debug, Deserialize)]
-        struct QuestionWrapper {
-            question: Question,
-        }
+            .wrap_err("Failed to fetch metadata from LeetCode")?;
 
-        #[derive(Debug, Deserialize)]
-        struct Data {
-            data: QuestionWrapper,
+        let metadata = data.json::<UserMetadata>().wrap_err(AuthError(
+            "Failed to parse metadata, Try renewing cookie".to_string(),
+        ))?;
+        if metadata.user_name.is_empty() {
+            bail!(AuthError("Cookie invalid. Renew cookies".to_string()));
         }
-
-        data.json::<Data>()
-            .wrap_err("Failed to parse question id from LeetCode")
-            .map(|opt| opt.data.question)
+        Ok(metadata)
     }
 }
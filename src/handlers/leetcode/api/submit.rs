@@ -1,16 +1,18 @@
 use crate::handlers::leetcode::*;
+use crate::output;
 
-use eyre::{bail, Context, Result};
+use eyre::{Context, Result};
 
 impl LeetCode<Authorized> {
-    pub fn submit(&self, codefile: &CodeFile) -> Result<SubmissionResult> {
-        let question_title = codefile.question_title.clone();
-        let ques = self.question_metadata(&question_title)?;
-        let question_id = ques.questionId;
+    pub fn submit(
+        &self,
+        codefile: &CodeFile,
+        question: &QuestionDetail,
+    ) -> Result<SubmissionResult> {
         self._submit(
             codefile.language.to_string(),
-            question_id,
-            question_title,
+            question.questionId.clone(),
+            codefile.question_title.clone(),
             codefile.code.clone(),
         )
     }
@@ -22,13 +24,15 @@ impl LeetCode<Authorized> {
         question_title: String,
         typed_code: String,
     ) -> Result<SubmissionResult> {
+        self.ensure_online("submit")?;
+
         #[derive(Debug, Deserialize)]
         struct SubmissionID {
             submission_id: u32,
         }
 
         let client = &self.client;
-        let url = format!("https://leetcode.com/problems/{}/submit/", question_title);
+        let url = self.url(&format!("problems/{}/submit/", question_title));
         let submission = SubmitCode {
             lang,
             question_id,
@@ -38,45 +42,13 @@ impl LeetCode<Authorized> {
             "Failed to fetch submission id from LeetCode, Check your submissions manually on leetcode"
         )?;
 
-        println!("Evaluating solution...");
-        let submission_id = data.submission_id;
-        let mut last_state = PendingState::Unknown;
-
-        loop {
-            let url = format!("https://leetcode.com/submissions/detail/{submission_id}/check/");
-            let data = client
-                .get(&url)
-                .send()?
-                .json::<SubmissionResult>()
-                .wrap_err(
-                    "Failed to fetch from leetcode! Try again after sometime or renew cookie",
-                )?;
+        let submission_id = data.submission_id.to_string();
+        output::queued("evaluating", &submission_id, "Evaluating solution...");
+        self.poll(&submission_id)
+    }
 
-            match data {
-                SubmissionResult::PendingResult(data) => {
-                    let curr_state = data.state();
-                    match curr_state {
-                        PendingState::Pending if last_state != PendingState::Pending => {
-                            println!("Status : Evaluation Pending");
-                        }
-                        PendingState::Started if last_state != PendingState::Started => {
-                            println!("Status : Execution Started");
-                        }
-                        PendingState::Success => {
-                            bail!("Your code was executed successfully but we failed to parse result\nCheck on leetcode manually");
-                        }
-                        PendingState::Unknown => {
-                            bail!(
-                                "Status : {}\nKindly report this state to developer",
-                                data.state
-                            );
-                        }
-                        _ => {}
-                    };
-                    last_state = curr_state;
-                }
-                not_pending_data => return Ok(not_pending_data),
-            };
-        }
+    /// Fetches the result of a submission, waiting while it's pending
+    pub fn check_submission(&self, submission_id: &str) -> Result<SubmissionResult> {
+        self.poll(submission_id)
     }
 }
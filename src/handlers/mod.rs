@@ -1,6 +1,8 @@
 pub mod execution;
-mod helpers;
+pub mod helpers;
 pub mod leetcode;
+pub mod problems;
+pub mod question;
 pub mod submission;
 pub mod user;
 pub mod utils;
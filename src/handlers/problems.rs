@@ -0,0 +1,312 @@
+use std::{fmt, str::FromStr};
+
+use super::helpers::Difficulty;
+use super::question::TopicTag;
+
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// A question of the problem set, as listed on the problems page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ProblemSummary {
+    pub frontendQuestionId: String,
+    pub title: String,
+    pub titleSlug: String,
+    pub difficulty: String,
+    /// Acceptance rate in percent
+    pub acRate: f64,
+    pub paidOnly: bool,
+    /// `ac` or `notac`, only known with a session
+    pub status: Option<String>,
+    pub topicTags: Vec<TopicTag>,
+}
+
+/// Progress of the user on a problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProblemStatus {
+    Solved,
+    Attempted,
+    Todo,
+}
+
+impl ProblemSummary {
+    pub fn status(&self) -> ProblemStatus {
+        match self.status.as_deref() {
+            Some("ac") => ProblemStatus::Solved,
+            Some("notac") => ProblemStatus::Attempted,
+            _ => ProblemStatus::Todo,
+        }
+    }
+}
+
+/// Filters of the problem set, shared by the commands picking problems
+#[derive(Debug, Default, Clone, Args)]
+pub struct ProblemFilter {
+    /// Only problems of this difficulty
+    #[arg(short, long)]
+    pub difficulty: Option<Difficulty>,
+    /// Only problems with this topic tag, by slug or name, repeat to require several
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+    /// Only problems with this progress, needs a session
+    #[arg(short, long, value_enum)]
+    pub status: Option<ProblemStatus>,
+    /// Only premium problems
+    #[arg(long, conflicts_with = "free")]
+    pub paid: bool,
+    /// Only problems available without premium
+    #[arg(long)]
+    pub free: bool,
+    /// Lowest acceptance rate in percent
+    #[arg(long, value_name = "PERCENT")]
+    pub min_acceptance: Option<f64>,
+    /// Highest acceptance rate in percent
+    #[arg(long, value_name = "PERCENT")]
+    pub max_acceptance: Option<f64>,
+    /// Words of the title or slug, or the problem number
+    #[arg(short, long)]
+    pub keyword: Option<String>,
+}
+
+impl ProblemFilter {
+    pub fn matches(&self, problem: &ProblemSummary) -> bool {
+        let difficulty = self.difficulty.is_none_or(|difficulty| {
+            Difficulty::from_str(&problem.difficulty).is_ok_and(|d| d == difficulty)
+        });
+        let tags = self.tag.iter().all(|tag| {
+            problem.topicTags.iter().any(|topic| {
+                topic.slug.eq_ignore_ascii_case(tag) || topic.name.eq_ignore_ascii_case(tag)
+            })
+        });
+        let status = self.status.is_none_or(|status| problem.status() == status);
+        let paid = match (self.paid, self.free) {
+            (true, _) => problem.paidOnly,
+            (_, true) => !problem.paidOnly,
+            _ => true,
+        };
+        let acceptance = self.min_acceptance.is_none_or(|min| problem.acRate >= min)
+            && self.max_acceptance.is_none_or(|max| problem.acRate <= max);
+        let keyword = self.keyword.as_deref().is_none_or(|keyword| {
+            let title = problem.title.to_lowercase();
+            problem.frontendQuestionId == keyword.trim()
+                || keyword
+                    .to_lowercase()
+                    .split_whitespace()
+                    .all(|word| title.contains(word) || problem.titleSlug.contains(word))
+        });
+        difficulty && tags && status && paid && acceptance && keyword
+    }
+}
+
+/// Problems a question name may refer to, the closest first. The name is
+/// a frontend number, a slug or a title, typos are tolerated in the words
+/// of titles but numbers, slugs and titles matching exactly win. Numbers and
+/// slugs only match exactly
+pub fn matching_problems<'a>(
+    problems: &'a [ProblemSummary],
+    name: &str,
+) -> Vec<&'a ProblemSummary> {
+    let name = name.trim();
+    let query = to_slug(name);
+    if let Some(problem) = problems
+        .iter()
+        .find(|problem| problem.frontendQuestionId == name || problem.titleSlug == query)
+    {
+        return vec![problem];
+    }
+    if query.is_empty() || name.chars().all(|c| c.is_ascii_digit()) || is_slug(name) {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(usize, &ProblemSummary)> = problems
+        .iter()
+        .filter(|problem| is_close(&query, &problem.titleSlug))
+        .map(|problem| (edit_distance(&query, &problem.titleSlug), problem))
+        .collect();
+    // stable, problems as close as each other stay in problem set order
+    scored.sort_by_key(|(distance, _)| *distance);
+    scored.into_iter().map(|(_, problem)| problem).collect()
+}
+
+/// Whether `question` could be a title slug
+pub fn is_slug(question: &str) -> bool {
+    !question.is_empty()
+        && !question.chars().all(|c| c.is_ascii_digit())
+        && question
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Words of `name` in lowercase joined by `-`, as in title slugs
+fn to_slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Whether every word of `query` starts a word of `slug` or is a typo away
+/// from one, or the whole of `query` is a typo away from `slug`
+fn is_close(query: &str, slug: &str) -> bool {
+    let words: Vec<&str> = slug.split('-').collect();
+    let word_matches = |word: &str| {
+        words.iter().any(|candidate| {
+            candidate.starts_with(word) || edit_distance(word, candidate) <= typos(word)
+        })
+    };
+    query.split('-').all(word_matches) || edit_distance(query, slug) <= typos(query)
+}
+
+/// Typos tolerated in a word, none in short ones which would match too much
+fn typos(word: &str) -> usize {
+    word.chars().count() / 4
+}
+
+/// Levenshtein distance of `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Problems matching the filters of `list`
+#[derive(Debug, Serialize)]
+pub struct ProblemList {
+    pub problems: Vec<ProblemSummary>,
+}
+
+impl fmt::Display for ProblemSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self.status() {
+            ProblemStatus::Solved => "✔".green(),
+            ProblemStatus::Attempted => "~".yellow(),
+            ProblemStatus::Todo => " ".normal(),
+        };
+        let difficulty = format!("{:6}", self.difficulty);
+        let difficulty = match Difficulty::from_str(&self.difficulty) {
+            Ok(Difficulty::Easy) => difficulty.bright_green(),
+            Ok(Difficulty::Medium) => difficulty.bright_yellow(),
+            Ok(Difficulty::Hard) => difficulty.bright_red(),
+            Err(_) => difficulty.normal(),
+        };
+        let paid = if self.paidOnly {
+            "$".yellow()
+        } else {
+            " ".normal()
+        };
+        write!(
+            f,
+            "{} {:>5} {} {} {:>5.1}%  {}",
+            status, self.frontendQuestionId, paid, difficulty, self.acRate, self.title
+        )
+    }
+}
+
+impl fmt::Display for ProblemList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for problem in &self.problems {
+            writeln!(f, "{}", problem)?;
+        }
+        write!(f, "{} problem(s)", self.problems.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems() -> Vec<ProblemSummary> {
+        [
+            ("1", "Two Sum", "two-sum"),
+            ("42", "Trapping Rain Water", "trapping-rain-water"),
+            ("50", "Pow(x, n)", "powx-n"),
+            (
+                "99",
+                "Recover Binary Search Tree",
+                "recover-binary-search-tree",
+            ),
+            (
+                "167",
+                "Two Sum II - Input Array Is Sorted",
+                "two-sum-ii-input-array-is-sorted",
+            ),
+            ("407", "Trapping Rain Water II", "trapping-rain-water-ii"),
+        ]
+        .into_iter()
+        .map(|(id, title, slug)| ProblemSummary {
+            frontendQuestionId: id.to_string(),
+            title: title.to_string(),
+            titleSlug: slug.to_string(),
+            difficulty: "Easy".to_string(),
+            acRate: 50.0,
+            paidOnly: false,
+            status: None,
+            topicTags: Vec::new(),
+        })
+        .collect()
+    }
+
+    fn slugs(problems: &[ProblemSummary], name: &str) -> Vec<String> {
+        matching_problems(problems, name)
+            .into_iter()
+            .map(|problem| problem.titleSlug.clone())
+            .collect()
+    }
+
+    #[test]
+    fn matches_exact_numbers_and_slugs() {
+        let problems = problems();
+        assert_eq!(slugs(&problems, " 42 "), ["trapping-rain-water"]);
+        assert_eq!(slugs(&problems, "two-sum"), ["two-sum"]);
+        assert_eq!(slugs(&problems, "Two Sum"), ["two-sum"]);
+    }
+
+    #[test]
+    fn matches_titles_with_punctuation() {
+        let problems = problems();
+        assert_eq!(slugs(&problems, "Pow(x, n)"), ["powx-n"]);
+        assert_eq!(
+            slugs(&problems, "Two Sum II - Input Array Is Sorted"),
+            ["two-sum-ii-input-array-is-sorted"]
+        );
+    }
+
+    #[test]
+    fn tolerates_a_typo() {
+        let problems = problems();
+        assert_eq!(
+            slugs(&problems, "Recover Binary Serch Tree"),
+            ["recover-binary-search-tree"]
+        );
+    }
+
+    #[test]
+    fn lists_every_candidate_of_ambiguous_names() {
+        let problems = problems();
+        assert_eq!(
+            slugs(&problems, "Rain Water"),
+            ["trapping-rain-water", "trapping-rain-water-ii"]
+        );
+    }
+
+    #[test]
+    fn numbers_and_slugs_only_match_exactly() {
+        let problems = problems();
+        assert!(slugs(&problems, "9999").is_empty());
+        assert!(slugs(&problems, "4").is_empty());
+        assert!(slugs(&problems, "two-sum-iii").is_empty());
+        assert!(slugs(&problems, "trapping-rain-watr").is_empty());
+    }
+}
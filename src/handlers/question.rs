@@ -0,0 +1,118 @@
+use super::helpers::BoilerPlateCode;
+use crate::file_parser::language::Language;
+
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Result};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+
+/// Extension of the schema saved next to the code of database questions,
+/// which isn't mistaken for a code file
+pub(crate) const SCHEMA_EXTENSION: &str = "schema.sql";
+
+/// Everything about a question, fetched once and shared by the commands
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct QuestionDetail {
+    pub questionId: String,
+    pub questionFrontendId: String,
+    pub title: String,
+    pub titleSlug: String,
+    pub difficulty: String,
+    pub isPaidOnly: bool,
+    pub topicTags: Vec<TopicTag>,
+    /// Missing for premium questions without a subscription
+    pub content: Option<String>,
+    pub mysqlSchemas: Vec<String>,
+    pub codeSnippets: Option<Vec<BoilerPlateCode>>,
+    pub exampleTestcaseList: Vec<String>,
+    pub metaData: String,
+    pub hints: Vec<String>,
+    #[serde(deserialize_with = "json_string")]
+    pub similarQuestions: Vec<SimilarQuestion>,
+    #[serde(deserialize_with = "json_string")]
+    pub stats: QuestionStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicTag {
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct SimilarQuestion {
+    pub title: String,
+    pub titleSlug: String,
+    pub difficulty: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+#[serde(default)]
+pub struct QuestionStats {
+    pub totalAccepted: String,
+    pub totalSubmission: String,
+    pub totalAcceptedRaw: u64,
+    pub totalSubmissionRaw: u64,
+    pub acRate: String,
+}
+
+impl QuestionDetail {
+    /// Whether the statement and code are hidden, as for premium questions
+    /// fetched without a subscription
+    pub(crate) fn is_locked(&self) -> bool {
+        self.content.is_none() || self.codeSnippets.is_none()
+    }
+
+    /// Statement of the question as HTML
+    pub fn content(&self) -> Result<&str> {
+        match &self.content {
+            Some(content) => Ok(content),
+            None => bail!("{} is only available with LeetCode Premium", self.title),
+        }
+    }
+
+    /// Boilerplate code in the languages supported by the cli
+    pub(crate) fn snippets(&self) -> impl Iterator<Item = &BoilerPlateCode> {
+        self.codeSnippets
+            .iter()
+            .flatten()
+            .filter(|code| code.is_supported())
+    }
+
+    /// Saves the statements creating the tables of a database question next
+    /// to its code file at `code_path`, returns where they were saved
+    pub(crate) fn save_schema(&self, code_path: &Path) -> Result<Option<PathBuf>> {
+        if self.mysqlSchemas.is_empty() {
+            return Ok(None);
+        }
+        let path = code_path.with_extension(SCHEMA_EXTENSION);
+        let statements = self
+            .mysqlSchemas
+            .iter()
+            .map(|statement| format!("{};\n", statement.trim().trim_end_matches(';')))
+            .collect::<String>();
+        std::fs::write(&path, statements)?;
+        Ok(Some(path))
+    }
+
+    /// Boilerplate code of the question in `language`
+    pub(crate) fn snippet(&self, language: Language) -> Option<&BoilerPlateCode> {
+        self.snippets()
+            .find(|code| code.language().ok() == Some(language))
+    }
+}
+
+/// LeetCode sends some fields as JSON encoded strings, or null when locked
+fn json_string<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(json) => serde_json::from_str(&json).map_err(serde::de::Error::custom),
+        None => Ok(T::default()),
+    }
+}
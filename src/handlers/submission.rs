@@ -1,7 +1,7 @@
 use colored::Colorize;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SubmitCorrect {
     pub submission_id: String,
     pub lang: String,
@@ -16,7 +16,7 @@ pub struct SubmitCorrect {
     pub memory_percentile: f64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SubmitLimitExceeded {
     pub submission_id: String,
     pub lang: String,
@@ -30,7 +30,7 @@ pub struct SubmitLimitExceeded {
     pub total_testcases: u8,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SubmitWrong {
     pub status_code: u8,
     pub lang: String,
@@ -53,7 +53,7 @@ pub struct SubmitWrong {
     pub input: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SubmitRuntimeError {
     pub status_code: u8,
     pub lang: String,
@@ -76,7 +76,7 @@ pub struct SubmitRuntimeError {
     pub state: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SubmitCompileError {
     pub status_code: u8,
     pub lang: String,
@@ -1,8 +1,11 @@
-use std::fmt;
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UserMetadata {
     pub user_name: String,
     pub num_total: u16,
@@ -29,3 +32,66 @@ impl fmt::Display for UserMetadata {
         )
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct AuthStatus {
+    #[serde(flatten)]
+    pub metadata: Option<UserMetadata>,
+    /// Active profile, if any
+    pub profile: Option<String>,
+    /// Where the cookie was read from
+    pub cookie_source: String,
+    /// Unix time the session expires at, when the cookie tells
+    pub expires_at: Option<u64>,
+}
+
+impl AuthStatus {
+    pub fn new(profile: Option<String>, cookie_source: String, expiry: Option<SystemTime>) -> Self {
+        Self {
+            metadata: None,
+            profile,
+            cookie_source,
+            expires_at: expiry
+                .and_then(|expiry| expiry.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map(|since_epoch| since_epoch.as_secs()),
+        }
+    }
+}
+
+impl fmt::Display for AuthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expiry = self.expires_at.and_then(|expires_at| {
+            SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(expires_at))
+        });
+        let expires = match expiry {
+            Some(expiry) => match expiry.duration_since(SystemTime::now()) {
+                Ok(left) => format!("in {}", days_and_hours(left)),
+                Err(_) => "expired".to_string(),
+            },
+            None => "unknown".to_string(),
+        };
+        if let Some(metadata) = &self.metadata {
+            writeln!(f, "{}", metadata)?;
+        }
+        write!(
+            f,
+            "{:18}:{:>15}\n{:18}:{:>15}\n{:18}:{:>15}",
+            "Profile",
+            self.profile.as_deref().unwrap_or("none"),
+            "Cookie source",
+            self.cookie_source,
+            "Session expires",
+            expires
+        )
+    }
+}
+
+/// Rough length of a session, e.g. `13d 5h`
+pub(crate) fn days_and_hours(duration: Duration) -> String {
+    let hours = duration.as_secs() / 3600;
+    match (hours / 24, hours % 24) {
+        (0, 0) => "less than an hour".to_string(),
+        (0, hours) => format!("{}h", hours),
+        (days, hours) => format!("{}d {}h", days, hours),
+    }
+}
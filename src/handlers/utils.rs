@@ -2,10 +2,11 @@ use std::fmt;
 
 use super::execution::*;
 use super::submission::*;
+use crate::output::Verdict;
 use colored::Colorize;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SubmissionResult {
     Success(SubmitCorrect),
@@ -17,7 +18,7 @@ pub enum SubmissionResult {
     Unknown(Unknown),
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ExecutionResult {
     Success(Success),
@@ -37,20 +38,12 @@ pub enum PendingState {
     Unknown,
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(non_snake_case)]
-pub struct Question {
-    pub questionId: String,
-    pub questionTitle: String,
-    pub exampleTestcaseList: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PendingResult {
     pub(crate) state: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Unknown {}
 
 impl fmt::Display for SubmissionResult {
@@ -67,6 +60,49 @@ impl fmt::Display for SubmissionResult {
     }
 }
 
+impl fmt::Display for ExecutionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionResult::Success(inner) => write!(f, "{}", inner),
+            ExecutionResult::CompileError(inner) => write!(f, "{}", inner),
+            ExecutionResult::RuntimeError(inner) => write!(f, "{}", inner),
+            ExecutionResult::LimitExceeded(inner) => write!(f, "{}", inner),
+            ExecutionResult::WrongTestcase(inner) => write!(f, "{}", inner),
+            ExecutionResult::PendingResult(inner) => write!(f, "{}", inner),
+            ExecutionResult::Unknown(_) => write!(f, "{}", "Unknown error occured".red().bold()),
+        }
+    }
+}
+
+impl ExecutionResult {
+    pub fn verdict(&self) -> Verdict {
+        match self {
+            ExecutionResult::Success(success) if success.is_correct() => Verdict::Accepted,
+            ExecutionResult::Success(_) => Verdict::WrongAnswer,
+            ExecutionResult::CompileError(_) => Verdict::CompileError,
+            ExecutionResult::RuntimeError(_) => Verdict::RuntimeError,
+            ExecutionResult::LimitExceeded(_) => Verdict::LimitExceeded,
+            ExecutionResult::WrongTestcase(_) => Verdict::InvalidTestcase,
+            ExecutionResult::PendingResult(_) => Verdict::Pending,
+            ExecutionResult::Unknown(_) => Verdict::Unknown,
+        }
+    }
+}
+
+impl SubmissionResult {
+    pub fn verdict(&self) -> Verdict {
+        match self {
+            SubmissionResult::Success(_) => Verdict::Accepted,
+            SubmissionResult::CompileError(_) => Verdict::CompileError,
+            SubmissionResult::RuntimeError(_) => Verdict::RuntimeError,
+            SubmissionResult::Wrong(_) => Verdict::WrongAnswer,
+            SubmissionResult::LimitExceeded(_) => Verdict::LimitExceeded,
+            SubmissionResult::PendingResult(_) => Verdict::Pending,
+            SubmissionResult::Unknown(_) => Verdict::Unknown,
+        }
+    }
+}
+
 impl PendingResult {
     pub fn state(&self) -> PendingState {
         match self.state.as_str() {
@@ -1,107 +1,308 @@
+use std::path::Path;
+
 use crate::args::Cli;
-use crate::file_parser::codefile::CodeFile;
-use crate::utils::{execute_testcases, pack, submit};
+use crate::cache::ResponseCache;
+use crate::config::Config;
+use crate::utils::{
+    check, configure, download, execute_locally, execute_testcases, language_choice, list,
+    load_code_file, login, logout, manage_cache, manage_profiles, manage_testcases, open_question,
+    pack, random_problem, resolve_question, save_html, submit,
+};
 
-use args::Commands;
+use args::{AuthCommands, Commands};
 use clap::Parser;
 use colored::Colorize;
-use eyre::{bail, Result};
-use handlers::leetcode::LeetCode;
+use cookies::CookieJar;
+use eyre::{bail, Context, Result};
+use handlers::leetcode::{Authorized, LeetCode, DEFAULT_BASE_URL};
+use handlers::user::AuthStatus;
+use output::{Verdict, VerdictError};
 
 mod args;
+mod cache;
+mod config;
+mod cookies;
+mod credentials;
 mod file_parser;
 mod handlers;
+mod output;
+mod runner;
+mod testcases;
 mod utils;
 
 const LC_COOKIE_ENV_KEY: &str = "LC_COOKIE";
 const GIT_README: &str = "README.md";
 const DAILY_CHALLENGE: &str = "daily_challenge.html";
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn main() {
+    // the arguments may name languages of the config
+    let config = Config::load();
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) => {
+            // only a warning so that --help and --version work with a broken config
+            if let Err(config_err) = &config {
+                eprintln!("{} {:#}", "Ignoring the config:".yellow(), config_err);
+            }
+            err.exit()
+        }
+    };
+    output::set_format(cli.format);
+
+    if let Err(err) = config.and_then(|config| run(cli, config)) {
+        std::process::exit(output::report_error(&err));
+    }
+}
+
+fn run(cli: Cli, mut config: Config) -> Result<()> {
+    let Some(command) = cli.command else {
+        return Ok(());
+    };
+
+    match command {
+        Commands::Config { command } => return configure(command, &config),
+        Commands::Cache { command } => return manage_cache(command, &config),
+        Commands::Testcase {
+            question,
+            file,
+            command,
+        } => return manage_testcases(command, question, file),
+        Commands::Profile { command } => {
+            return manage_profiles(command, &config, cli.profile.as_deref())
+        }
+        _ => {}
+    }
+
+    let profile = config.active_profile(cli.profile.as_deref())?;
+    if let Some((name, settings)) = &profile {
+        credentials::set_profile(name);
+        config = config.with_profile(settings);
+    }
+    if let Commands::Auth {
+        command: Some(AuthCommands::Logout),
+    } = command
+    {
+        return logout();
+    }
 
-    let cookie = std::env::var_os(LC_COOKIE_ENV_KEY)
-        .ok_or_else(|| eyre::eyre!("{} is not set in the environment.", LC_COOKIE_ENV_KEY))?
-        .into_string()
-        .map_err(|_| eyre::eyre!("Invalid Unicode found"))?;
+    let base_url = cli
+        .base_url
+        .as_deref()
+        .or(config.base_url.as_deref())
+        .unwrap_or(DEFAULT_BASE_URL);
 
-    let lc = LeetCode::new().authenticate(&cookie)?;
+    let mut polling = config.polling();
+    if let Some(timeout) = cli.timeout {
+        polling.timeout = timeout.0;
+    }
 
-    match cli.command {
-        Some(Commands::Auth) => match lc.get_metadata() {
-            Ok(metadata) => println!("{}", metadata),
-            Err(err) => bail!(err),
+    let cache = ResponseCache::new(base_url, config.cache_ttl(), cli.offline);
+    if cli.offline && cache.is_none() {
+        bail!("--offline needs a cache directory, none is available on this platform");
+    }
+
+    let lc = LeetCode::new()
+        .with_base_url(base_url)?
+        .with_polling(polling)
+        .with_cache(cache);
+    if let Commands::Auth {
+        command: Some(AuthCommands::Login { encrypt }),
+    } = command
+    {
+        return login(&lc, cli.cookie_from, encrypt);
+    }
+
+    let (profile, workspace) = match profile {
+        Some((name, settings)) => (Some(name), settings.workspace),
+        None => (None, None),
+    };
+    let command = match workspace {
+        Some(workspace) => enter_workspace(command, &workspace)?,
+        None => command,
+    };
+
+    let cookie_source = cli.cookie_from.unwrap_or_else(|| config.cookie_source());
+    if command.needs_auth() {
+        let jar = CookieJar::parse(&cookie_source.read(&lc.host())?, &lc.host())?;
+        let lc = lc.authenticate(&jar)?;
+        let status = AuthStatus::new(profile, cookie_source.to_string(), jar.session_expiry());
+        return run_authorized(command, &lc, &config, status);
+    }
+
+    // the session still helps public commands, premium questions need it.
+    // Sources asking for a passphrase or copying a browser database are
+    // only read once a question turns out to be locked, except by download
+    // whose concurrent requests can't wait for it
+    if cookie_source.is_expensive() && !matches!(command, Commands::Download { .. }) {
+        return run_public(command, &lc.with_late_session(cookie_source), &config);
+    }
+    let jar = cookie_source
+        .read(&lc.host())
+        .and_then(|cookie| CookieJar::parse(&cookie, &lc.host()))
+        .ok()
+        .filter(|jar| jar.require_session().is_ok() && !jar.is_expired());
+    match jar {
+        Some(jar) => run_public(command, &lc.authenticate(&jar)?, &config),
+        None => run_public(command, &lc, &config),
+    }
+}
+
+/// Moves into the profile's workspace for the commands saving questions,
+/// paths given on the command line stay relative to where the tool was started
+fn enter_workspace(command: Commands, workspace: &Path) -> Result<Commands> {
+    let command = match command {
+        Commands::Pack { file } => Commands::Pack {
+            file: file.map(std::path::absolute).transpose()?,
+        },
+        Commands::Download {
+            questions,
+            from_file,
+            lang,
+            output,
+            jobs,
+        } => Commands::Download {
+            questions,
+            from_file: from_file.map(std::path::absolute).transpose()?,
+            lang,
+            output,
+            jobs,
         },
-        Some(Commands::DailyChallenge { no_code_save }) => {
+        Commands::Question { .. } | Commands::DailyChallenge { .. } | Commands::Random { .. } => {
+            command
+        }
+        _ => return Ok(command),
+    };
+    std::fs::create_dir_all(workspace)?;
+    std::env::set_current_dir(workspace)
+        .wrap_err_with(|| format!("Failed to enter the workspace {}", workspace.display()))?;
+    Ok(command)
+}
+
+/// Runs a command which only needs public question data, with or without a session
+fn run_public<State>(command: Commands, lc: &LeetCode<State>, config: &Config) -> Result<()> {
+    match command {
+        Commands::DailyChallenge {
+            no_code_save,
+            lang,
+            output,
+        } => {
             let daily_challenge = lc.get_daily_challenge()?;
-            println!("Today's Daily Challenge:\n{}", daily_challenge);
+            if output::is_text() {
+                println!("Today's Daily Challenge:");
+            }
+            output::result("daily", None, &daily_challenge);
             let title = daily_challenge.question.titleSlug;
+            let question = lc.question_detail(&title)?;
             if !no_code_save {
-                lc.save_boiler_code(&title)?;
+                lc.save_boiler_code(
+                    &question,
+                    language_choice(lang, config),
+                    output.as_deref().or(config.filename.as_deref()),
+                )?;
             }
 
-            let question = lc.question_content(&title)?;
-            std::fs::write(DAILY_CHALLENGE, question.content)?;
-            println!("Saved question as HTML to {}", DAILY_CHALLENGE.cyan());
-            open::that(DAILY_CHALLENGE)?;
+            let path = save_html(config, DAILY_CHALLENGE, question.content()?)?;
+            open::that(path)?;
         }
-        Some(Commands::Question {
+        Commands::Question {
             question_name,
             no_code_save,
-        }) => {
-            let question_name = if let Some(idx) = question_name.find("leetcode.com/problems/") {
-                let question_title = question_name[idx..]
-                    .split_whitespace()
-                    .next()
-                    .expect("Should be Some since the find method succeed")
-                    .split('/')
-                    .skip(2)
-                    .next()
-                    .ok_or_else(|| eyre::eyre!("Invalid link, expected question identifier"))?;
-                question_title
-            } else {
-                &question_name
-            };
-            if !no_code_save {
-                lc.save_boiler_code(question_name)?;
+            lang,
+            output,
+        } => open_question(
+            lc,
+            config,
+            &resolve_question(lc, &question_name)?,
+            no_code_save,
+            lang,
+            output,
+        )?,
+        Commands::Random {
+            filter,
+            unsolved,
+            seed,
+            no_code_save,
+            lang,
+            output,
+        } => {
+            let problem = random_problem(lc, &filter, unsolved, seed, config.pack_layout())?;
+            output::result("random", None, &problem);
+            open_question(lc, config, &problem.titleSlug, no_code_save, lang, output)?;
+        }
+        Commands::Run {
+            file,
+            testcase_file: testcases,
+            expected_file,
+            ..
+        } => {
+            let code_file = load_code_file(file)?;
+            let question = lc.question_detail(&code_file.question_title)?;
+            if !execute_locally(&code_file, &question, testcases, expected_file)? {
+                bail!(VerdictError(Verdict::WrongAnswer));
             }
+        }
+        Commands::List { filter, slugs } => list(lc, &filter, slugs)?,
+        Commands::Download {
+            questions,
+            from_file,
+            lang,
+            output,
+            jobs,
+        } => download(lc, config, questions, from_file, lang, output, jobs)?,
+        Commands::Pack { file } => pack(lc, file, config.pack_layout())?,
+        _ => unreachable!("command needs authentication"),
+    };
+
+    Ok(())
+}
 
-            let question = lc.question_content(question_name)?;
-            let filename = format!("{}.html", question_name);
-            // save to filename
-            std::fs::write(&filename, question.content)?;
-            println!("Saved question as HTML to {}", filename.cyan());
-            open::that(filename)?;
+/// Runs a command which needs a logged in session, `status` tells about it
+fn run_authorized(
+    command: Commands,
+    lc: &LeetCode<Authorized>,
+    config: &Config,
+    status: AuthStatus,
+) -> Result<()> {
+    match command {
+        Commands::Auth { .. } => {
+            let status = AuthStatus {
+                metadata: Some(lc.get_metadata()?),
+                ..status
+            };
+            output::result("auth", None, &status);
         }
-        Some(Commands::Run {
+        Commands::Run {
             file,
             testcase_file: testcases,
-        }) => {
-            execute_testcases(file, testcases, &lc)?;
+            ..
+        } => {
+            let code_file = load_code_file(file)?;
+            let question = lc.question_detail(&code_file.question_title)?;
+            if !execute_testcases(&code_file, &question, testcases, lc)? {
+                bail!(VerdictError(Verdict::WrongAnswer));
+            }
         }
-        Some(Commands::FastSubmit { file }) => {
-            let code_file = if let Some(path) = file {
-                CodeFile::from_file(&path)?
-            } else {
-                CodeFile::from_dir(".")?
-            };
-
-            submit(&lc, code_file)?;
+        Commands::FastSubmit { file } => {
+            let code_file = load_code_file(file)?;
+            let question = lc.question_detail(&code_file.question_title)?;
+            submit(lc, &code_file, &question)?;
         }
-        Some(Commands::Submit {
+        Commands::Submit {
             file,
             testcase_file: testcases,
-        }) => {
-            let (is_correct, code_file) = execute_testcases(file, testcases, &lc)?;
-            if is_correct {
-                submit(&lc, code_file)?;
+        } => {
+            let code_file = load_code_file(file)?;
+            let question = lc.question_detail(&code_file.question_title)?;
+            if execute_testcases(&code_file, &question, testcases, lc)? {
+                submit(lc, &code_file, &question)?;
             } else {
-                bail!("Aborting submission due to failed testcase(s)".red().bold());
+                output::info("Aborting submission due to failed testcase(s)".red().bold());
+                bail!(VerdictError(Verdict::WrongAnswer));
             }
         }
-        Some(Commands::Pack { file }) => pack(&lc, file)?,
-
-        None => {}
+        Commands::Check { id } => check(lc, &id)?,
+        // public commands needing the session for some of their options
+        command => run_public(command, lc, config)?,
     };
 
     Ok(())
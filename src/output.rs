@@ -0,0 +1,242 @@
+//! Machine readable output for editor plugins and scripts
+//!
+//! With `--format json` a command prints a single result object, `ndjson`
+//! additionally streams status events while the result is pending.
+//! The schema is documented in the README, fields are only ever added.
+
+use std::{fmt, sync::OnceLock};
+
+use crate::handlers::leetcode::PollError;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable, colored output
+    #[default]
+    Text,
+    /// A single JSON object with the result
+    Json,
+    /// Newline delimited JSON, status events followed by the result
+    Ndjson,
+}
+
+/// Outcome of running or submitting a solution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Accepted,
+    WrongAnswer,
+    CompileError,
+    RuntimeError,
+    /// Time, memory or output limit exceeded
+    LimitExceeded,
+    InvalidTestcase,
+    Pending,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Verdict::Accepted => 0,
+            Verdict::WrongAnswer => 2,
+            Verdict::CompileError => 3,
+            Verdict::RuntimeError => 4,
+            Verdict::LimitExceeded => 5,
+            Verdict::InvalidTestcase => 7,
+            Verdict::Pending | Verdict::Unknown => 9,
+        }
+    }
+}
+
+/// Exit code of failures which aren't a verdict
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code when LeetCode couldn't be reached
+pub const EXIT_NETWORK: i32 = 6;
+/// Exit code when the session cookie is missing or rejected
+pub const EXIT_AUTH: i32 = 8;
+/// Exit code when interrupted with Ctrl-C
+pub const EXIT_CANCELLED: i32 = 130;
+
+/// Failed verdict whose result was already reported, carried through
+/// `eyre` so that `main` exits with the verdict's code
+#[derive(Debug)]
+pub struct VerdictError(pub Verdict);
+
+impl fmt::Display for VerdictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::error::Error for VerdictError {}
+
+/// Missing or rejected session cookie
+#[derive(Debug)]
+pub struct AuthError(pub String);
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Request which needs LeetCode while `--offline`
+#[derive(Debug)]
+pub struct OfflineError(pub String);
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a, T: Serialize> {
+    Status {
+        state: &'a str,
+        /// Id of the run or submission, reported once LeetCode accepted it
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<&'a str>,
+    },
+    Result {
+        command: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verdict: Option<Verdict>,
+        result: &'a T,
+    },
+    Error {
+        kind: &'a str,
+        message: &'a str,
+        /// Id of the run or submission which is still pending
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<&'a str>,
+    },
+}
+
+/// Sets the output format, only the first call has an effect
+pub fn set_format(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+pub fn is_text() -> bool {
+    format() == OutputFormat::Text
+}
+
+/// Prints an informational message, to stderr unless the output is text
+/// so that stdout only holds JSON
+pub fn info(message: impl fmt::Display) {
+    match format() {
+        OutputFormat::Text => println!("{}", message),
+        OutputFormat::Json | OutputFormat::Ndjson => eprintln!("{}", message),
+    }
+}
+
+/// Reports progress of a pending result, `state` is the stable name used
+/// in status events and `message` is shown in text output
+pub fn status(state: &str, message: impl fmt::Display) {
+    match format() {
+        OutputFormat::Text => println!("{}", message),
+        OutputFormat::Json => {}
+        OutputFormat::Ndjson => emit::<()>(&Event::Status { state, id: None }),
+    }
+}
+
+/// Reports that LeetCode accepted a run or submission, its `id` can be
+/// used to check the result later
+pub fn queued(state: &str, id: &str, message: impl fmt::Display) {
+    match format() {
+        OutputFormat::Text => println!("{} (id {})", message, id),
+        OutputFormat::Json => {}
+        OutputFormat::Ndjson => emit::<()>(&Event::Status {
+            state,
+            id: Some(id),
+        }),
+    }
+}
+
+/// Prints the result of `command`, with its human readable form in text output
+pub fn result<T: Serialize + fmt::Display>(command: &str, verdict: Option<Verdict>, result: &T) {
+    match format() {
+        OutputFormat::Text => println!("{}", result),
+        OutputFormat::Json | OutputFormat::Ndjson => emit(&Event::Result {
+            command,
+            verdict,
+            result,
+        }),
+    }
+}
+
+/// Prints `err` unless it's a verdict which was already reported,
+/// returns the exit code of the process
+pub fn report_error(err: &eyre::Report) -> i32 {
+    if let Some(VerdictError(verdict)) = err.downcast_ref::<VerdictError>() {
+        return verdict.exit_code();
+    }
+    let pending = err.downcast_ref::<PollError>();
+    let (kind, code) = if let Some(pending) = pending {
+        match pending {
+            PollError::Cancelled { .. } => ("cancelled", EXIT_CANCELLED),
+            PollError::TimedOut { .. } => ("timeout", Verdict::Pending.exit_code()),
+        }
+    } else if err.downcast_ref::<AuthError>().is_some() {
+        ("auth", EXIT_AUTH)
+    } else if err.downcast_ref::<OfflineError>().is_some() {
+        ("offline", EXIT_NETWORK)
+    } else if is_network_error(err) {
+        ("network", EXIT_NETWORK)
+    } else {
+        ("error", EXIT_FAILURE)
+    };
+    match format() {
+        OutputFormat::Text => {
+            eprintln!("Error: {:?}", err);
+            if let Some(pending) = pending {
+                eprintln!(
+                    "The result is kept by LeetCode, resume with `check {}`",
+                    pending.id()
+                );
+            }
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => emit::<()>(&Event::Error {
+            kind,
+            message: &format!("{:#}", err),
+            id: pending.map(PollError::id),
+        }),
+    }
+    code
+}
+
+/// Whether LeetCode couldn't be reached, decoding errors mean it answered
+/// but not with what we expected
+pub fn is_network_error(err: &eyre::Report) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|err| !err.is_decode())
+    })
+}
+
+fn emit<T: Serialize>(event: &Event<'_, T>) {
+    let json = match format() {
+        OutputFormat::Json => serde_json::to_string_pretty(event),
+        _ => serde_json::to_string(event),
+    };
+    match json {
+        Ok(json) => println!("{}", json),
+        Err(err) => eprintln!("Failed to serialize output: {}", err),
+    }
+}
@@ -0,0 +1,116 @@
+//! Runs the scripts of shell questions on the file their statement describes
+//!
+//! Shell questions read a file like `file.txt` from the working directory.
+//! Its example content, and the output expected from it, are taken from the
+//! statement since these questions have no usual testcases.
+
+use super::*;
+
+use serde_json::Value;
+
+pub(super) const TOOLCHAIN: Toolchain = Toolchain {
+    source: "script.sh",
+    build: None,
+    run: &["bash", "{src}"],
+};
+
+/// Name of the file read when the statement doesn't tell
+const DEFAULT_INPUT_FILE: &str = "file.txt";
+
+/// Name of the file the script of a shell question reads, as told by the
+/// statement in HTML
+pub(crate) fn input_file(content: &str) -> String {
+    strip_tags(content)
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '"' | '\'' | '`'))
+        .map(|word| word.trim_end_matches('.'))
+        .find(|word| word.len() > ".txt".len() && word.ends_with(".txt"))
+        .unwrap_or(DEFAULT_INPUT_FILE)
+        .to_string()
+}
+
+/// Content of the input file in the example of the statement, with the
+/// output expected from it. They are the first two preformatted blocks
+pub(crate) fn example(content: &str) -> Result<(String, Option<String>)> {
+    let mut blocks = preformatted(content);
+    let Some(input) = blocks.next() else {
+        bail!("No example file found in the question, run it with a testcase file");
+    };
+    Ok((input, blocks.next()))
+}
+
+/// Runs the script of `code_file` once per file content of `files`, saved
+/// as `file_name` in the working directory of the script
+pub(crate) fn run(
+    code_file: &CodeFile,
+    file_name: &str,
+    files: Vec<String>,
+    expected: Vec<Option<String>>,
+) -> Result<LocalRun> {
+    let work_dir = work_dir(code_file)?;
+    let dir = work_dir.path();
+    let src = dir.join(TOOLCHAIN.source);
+    std::fs::write(&src, &code_file.code)?;
+
+    let mut outputs = Vec::with_capacity(files.len());
+    let mut error = None;
+    for (idx, file) in files.iter().enumerate() {
+        // like files of the judge, and so that the last line is read by `read`
+        let file = format!("{}\n", file.trim_end_matches('\n'));
+        std::fs::write(dir.join(file_name), file)?;
+        let output = command(TOOLCHAIN.run, dir, &src, &src)?
+            .output()
+            .wrap_err_with(|| format!("Failed to execute {}", TOOLCHAIN.run[0]))?;
+        if !output.status.success() {
+            error = Some(LocalError::Runtime {
+                testcase: idx + 1,
+                message: String::from_utf8_lossy(&output.stderr).to_string(),
+            });
+            break;
+        }
+        outputs.push(CaseOutput {
+            answer: String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string(),
+            std_output: String::from_utf8_lossy(&output.stderr)
+                .trim_end()
+                .to_string(),
+        });
+    }
+    Ok(LocalRun {
+        inputs: files
+            .into_iter()
+            .map(|file| vec![Value::String(file)])
+            .collect(),
+        outputs,
+        expected,
+        error,
+    })
+}
+
+/// Text of the `<pre>` blocks of `content`
+fn preformatted(content: &str) -> impl Iterator<Item = String> + '_ {
+    content.split("<pre>").skip(1).filter_map(|block| {
+        let (block, _) = block.split_once("</pre>")?;
+        Some(strip_tags(block).trim_matches('\n').to_string())
+    })
+}
+
+/// Text of HTML, without its tags and with the usual entities decoded
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
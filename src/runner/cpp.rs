@@ -0,0 +1,277 @@
+use super::signature::{node_values, ParamType, Signature};
+use super::*;
+
+pub(super) const TOOLCHAIN: Toolchain = Toolchain {
+    source: "main.cpp",
+    build: Some(&["g++", "-std=c++17", "-O2", "-o", "{bin}", "{src}"]),
+    run: &["{bin}"],
+};
+
+const PRELUDE: &str = r#"#include <algorithm>
+#include <bitset>
+#include <climits>
+#include <cmath>
+#include <cstdio>
+#include <deque>
+#include <functional>
+#include <iostream>
+#include <map>
+#include <numeric>
+#include <optional>
+#include <queue>
+#include <set>
+#include <sstream>
+#include <stack>
+#include <string>
+#include <unordered_map>
+#include <unordered_set>
+#include <vector>
+
+using namespace std;
+"#;
+
+const LIST_NODE: &str = r#"
+struct ListNode {
+    int val;
+    ListNode *next;
+    ListNode() : val(0), next(nullptr) {}
+    ListNode(int x) : val(x), next(nullptr) {}
+    ListNode(int x, ListNode *next) : val(x), next(next) {}
+};
+"#;
+
+const TREE_NODE: &str = r#"
+struct TreeNode {
+    int val;
+    TreeNode *left;
+    TreeNode *right;
+    TreeNode() : val(0), left(nullptr), right(nullptr) {}
+    TreeNode(int x) : val(x), left(nullptr), right(nullptr) {}
+    TreeNode(int x, TreeNode *left, TreeNode *right) : val(x), left(left), right(right) {}
+};
+"#;
+
+const HELPERS: &str = r#"
+string lc_format(int value) { return to_string(value); }
+string lc_format(long long value) { return to_string(value); }
+string lc_format(double value) {
+    char buffer[64];
+    snprintf(buffer, sizeof(buffer), "%.5f", value);
+    return buffer;
+}
+string lc_format(bool value) { return value ? "true" : "false"; }
+string lc_format(const string &value) {
+    string quoted = "\"";
+    for (char c : value) {
+        if (c == '"' || c == '\\') quoted += '\\';
+        quoted += c;
+    }
+    return quoted + "\"";
+}
+string lc_format(char value) { return lc_format(string(1, value)); }
+string lc_format(ListNode *node) {
+    string items;
+    for (; node; node = node->next) {
+        if (!items.empty()) items += ",";
+        items += to_string(node->val);
+    }
+    return "[" + items + "]";
+}
+string lc_format(TreeNode *root) {
+    vector<string> items;
+    deque<TreeNode *> queue = {root};
+    while (!queue.empty()) {
+        TreeNode *node = queue.front();
+        queue.pop_front();
+        if (node) {
+            items.push_back(to_string(node->val));
+            queue.push_back(node->left);
+            queue.push_back(node->right);
+        } else {
+            items.push_back("null");
+        }
+    }
+    while (!items.empty() && items.back() == "null") items.pop_back();
+    string joined;
+    for (size_t i = 0; i < items.size(); i++) joined += (i ? "," : "") + items[i];
+    return "[" + joined + "]";
+}
+string lc_format(const vector<bool> &values) {
+    string items;
+    for (size_t i = 0; i < values.size(); i++) items += (i ? "," : "") + lc_format((bool)values[i]);
+    return "[" + items + "]";
+}
+template <typename T> string lc_format(const vector<T> &values) {
+    string items;
+    for (size_t i = 0; i < values.size(); i++) items += (i ? "," : "") + lc_format(values[i]);
+    return "[" + items + "]";
+}
+
+ListNode *lc_list(const vector<int> &values) {
+    ListNode *head = nullptr;
+    for (auto it = values.rbegin(); it != values.rend(); ++it) head = new ListNode(*it, head);
+    return head;
+}
+
+TreeNode *lc_tree(const vector<optional<int>> &values) {
+    if (values.empty() || !values[0]) return nullptr;
+    TreeNode *root = new TreeNode(*values[0]);
+    deque<TreeNode *> queue = {root};
+    size_t i = 1;
+    while (!queue.empty() && i < values.size()) {
+        TreeNode *node = queue.front();
+        queue.pop_front();
+        if (i < values.size() && values[i]) {
+            node->left = new TreeNode(*values[i]);
+            queue.push_back(node->left);
+        }
+        i++;
+        if (i < values.size() && values[i]) {
+            node->right = new TreeNode(*values[i]);
+            queue.push_back(node->right);
+        }
+        i++;
+    }
+    return root;
+}
+"#;
+
+pub(super) fn generate(signature: &Signature, code: &str, cases: &[Vec<Value>]) -> Result<String> {
+    let mut source = String::from(PRELUDE);
+    if !defines(code, &["struct ListNode"]) {
+        source.push_str(LIST_NODE);
+    }
+    if !defines(code, &["struct TreeNode"]) {
+        source.push_str(TREE_NODE);
+    }
+    source.push_str(HELPERS);
+    source.push('\n');
+    source.push_str(code);
+    source.push_str("\n\nint main() {\n");
+
+    let output_param = signature.output_param();
+    for case in cases {
+        source.push_str("    {\n        Solution solution;\n");
+        let mut args = Vec::with_capacity(case.len());
+        for (i, (param, value)) in signature.params.iter().zip(case).enumerate() {
+            source.push_str(&format!(
+                "        {} a{} = {};\n",
+                type_name(&param.param_type)?,
+                i,
+                literal(&param.param_type, value)?
+            ));
+            args.push(format!("a{}", i));
+        }
+        let call = format!("solution.{}({})", signature.name, args.join(", "));
+        match output_param {
+            Some(idx) => source.push_str(&format!(
+                "        {};\n        cout << \"\\n{}\" << lc_format(a{}) << endl;\n",
+                call, OUTPUT_MARKER, idx
+            )),
+            // the answer is computed first as the solution may print as well
+            None => source.push_str(&format!(
+                "        auto answer = {};\n        cout << \"\\n{}\" << lc_format(answer) << endl;\n",
+                call, OUTPUT_MARKER
+            )),
+        }
+        source.push_str("    }\n");
+    }
+    source.push_str("    return 0;\n}\n");
+    Ok(source)
+}
+
+fn type_name(param_type: &ParamType) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Integer => "int".to_string(),
+        ParamType::Long => "long long".to_string(),
+        ParamType::Double => "double".to_string(),
+        ParamType::Boolean => "bool".to_string(),
+        ParamType::String => "string".to_string(),
+        ParamType::Character => "char".to_string(),
+        ParamType::Array(inner) | ParamType::List(inner) => {
+            format!("vector<{}>", type_name(inner)?)
+        }
+        ParamType::ListNode => "ListNode*".to_string(),
+        ParamType::TreeNode => "TreeNode*".to_string(),
+        ParamType::Void => bail!("Arguments can't be void"),
+    })
+}
+
+fn literal(param_type: &ParamType, value: &Value) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Integer => expect_i32(value)?.to_string(),
+        ParamType::Long => format!("{}LL", expect_i64(value)?),
+        ParamType::Double => float_literal(expect_f64(value)?),
+        ParamType::Boolean => expect_bool(value)?.to_string(),
+        ParamType::String => format!("string(\"{}\")", escape(expect_str(value)?, '"')),
+        ParamType::Character => format!("'{}'", escape(&expect_char(value)?.to_string(), '\'')),
+        ParamType::Array(inner) | ParamType::List(inner) => format!(
+            "{}{{{}}}",
+            type_name(param_type)?,
+            expect_array(value)?
+                .iter()
+                .map(|value| literal(inner, value))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        ParamType::ListNode => format!(
+            "lc_list({{{}}})",
+            node_values(value)?
+                .iter()
+                .map(|val| val.map(|val| val.to_string()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::TreeNode => format!(
+            "lc_tree({{{}}})",
+            node_values(value)?
+                .iter()
+                .map(|val| match val {
+                    Some(val) => val.to_string(),
+                    None => "nullopt".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::Void => bail!("Arguments can't be void"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal_of(param_type: &str, value: &str) -> Result<String> {
+        literal(
+            &param_type.parse().unwrap(),
+            &serde_json::from_str(value).unwrap(),
+        )
+    }
+
+    #[test]
+    fn writes_cpp_literals() {
+        assert_eq!(
+            literal_of("integer[][]", "[[1,-2],[]]").unwrap(),
+            "vector<vector<int>>{vector<int>{1, -2}, vector<int>{}}"
+        );
+        assert_eq!(
+            literal_of("list<string>", r#"["a\"b\n", ""]"#).unwrap(),
+            r#"vector<string>{string("a\"b\n"), string("")}"#
+        );
+        assert_eq!(literal_of("long", "-3000000000").unwrap(), "-3000000000LL");
+        assert_eq!(literal_of("character", r#""'""#).unwrap(), r"'\''");
+        assert_eq!(
+            literal_of("TreeNode", "[1,null,-3]").unwrap(),
+            "lc_tree({1, nullopt, -3})"
+        );
+        assert_eq!(
+            literal_of("ListNode", "[-1,2]").unwrap(),
+            "lc_list({-1, 2})"
+        );
+    }
+
+    #[test]
+    fn rejects_ints_out_of_range() {
+        assert!(literal_of("integer", "-3000000000").is_err());
+    }
+}
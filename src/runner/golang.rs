@@ -0,0 +1,263 @@
+use super::signature::{node_values, ParamType, Signature};
+use super::*;
+
+pub(super) const TOOLCHAIN: Toolchain = Toolchain {
+    source: "main.go",
+    build: Some(&["go", "build", "-o", "{bin}", "{src}"]),
+    run: &["{bin}"],
+};
+
+/// Packages imported by LeetCode for Go solutions, only imported when used
+/// since unused imports fail the compilation
+const PACKAGES: [(&str, &str); 7] = [
+    ("sort", "sort"),
+    ("math", "math"),
+    ("strconv", "strconv"),
+    ("unicode", "unicode"),
+    ("bits", "math/bits"),
+    ("heap", "container/heap"),
+    ("rand", "math/rand"),
+];
+
+const LIST_NODE: &str = r#"
+type ListNode struct {
+	Val  int
+	Next *ListNode
+}
+"#;
+
+const TREE_NODE: &str = r#"
+type TreeNode struct {
+	Val   int
+	Left  *TreeNode
+	Right *TreeNode
+}
+"#;
+
+const HELPERS: &str = r#"
+func lcFormat(value interface{}) string {
+	switch v := value.(type) {
+	case nil:
+		return "null"
+	case float64:
+		return fmt.Sprintf("%.5f", v)
+	case string:
+		return fmt.Sprintf("%q", v)
+	case byte:
+		return fmt.Sprintf("%q", string(v))
+	case *ListNode:
+		items := []string{}
+		for ; v != nil; v = v.Next {
+			items = append(items, fmt.Sprint(v.Val))
+		}
+		return "[" + strings.Join(items, ",") + "]"
+	case *TreeNode:
+		items := []string{}
+		queue := []*TreeNode{v}
+		for len(queue) > 0 {
+			node := queue[0]
+			queue = queue[1:]
+			if node == nil {
+				items = append(items, "null")
+				continue
+			}
+			items = append(items, fmt.Sprint(node.Val))
+			queue = append(queue, node.Left, node.Right)
+		}
+		for len(items) > 0 && items[len(items)-1] == "null" {
+			items = items[:len(items)-1]
+		}
+		return "[" + strings.Join(items, ",") + "]"
+	}
+	rv := reflect.ValueOf(value)
+	if rv.Kind() == reflect.Slice {
+		items := make([]string, rv.Len())
+		for i := range items {
+			items[i] = lcFormat(rv.Index(i).Interface())
+		}
+		return "[" + strings.Join(items, ",") + "]"
+	}
+	return fmt.Sprint(value)
+}
+
+func lcList(values []int) *ListNode {
+	var head *ListNode
+	for i := len(values) - 1; i >= 0; i-- {
+		head = &ListNode{Val: values[i], Next: head}
+	}
+	return head
+}
+
+func lcTree(values []interface{}) *TreeNode {
+	if len(values) == 0 || values[0] == nil {
+		return nil
+	}
+	root := &TreeNode{Val: values[0].(int)}
+	queue := []*TreeNode{root}
+	for i := 1; len(queue) > 0 && i < len(values); i += 2 {
+		node := queue[0]
+		queue = queue[1:]
+		if values[i] != nil {
+			node.Left = &TreeNode{Val: values[i].(int)}
+			queue = append(queue, node.Left)
+		}
+		if i+1 < len(values) && values[i+1] != nil {
+			node.Right = &TreeNode{Val: values[i+1].(int)}
+			queue = append(queue, node.Right)
+		}
+	}
+	return root
+}
+"#;
+
+pub(super) fn generate(signature: &Signature, code: &str, cases: &[Vec<Value>]) -> Result<String> {
+    let mut source =
+        String::from("package main\n\nimport (\n\t\"fmt\"\n\t\"reflect\"\n\t\"strings\"\n");
+    for (name, path) in PACKAGES {
+        if uses_package(code, name) {
+            source.push_str(&format!("\t\"{}\"\n", path));
+        }
+    }
+    source.push_str(")\n");
+    if !defines(code, &["type ListNode struct"]) {
+        source.push_str(LIST_NODE);
+    }
+    if !defines(code, &["type TreeNode struct"]) {
+        source.push_str(TREE_NODE);
+    }
+    source.push_str(HELPERS);
+    source.push('\n');
+    source.push_str(code);
+    source.push_str("\n\nfunc main() {\n");
+
+    let output_param = signature.output_param();
+    for case in cases {
+        source.push_str("\t{\n");
+        let mut args = Vec::with_capacity(case.len());
+        for (i, (param, value)) in signature.params.iter().zip(case).enumerate() {
+            source.push_str(&format!(
+                "\t\tvar a{} {} = {}\n",
+                i,
+                type_name(&param.param_type)?,
+                literal(&param.param_type, value)?
+            ));
+            args.push(format!("a{}", i));
+        }
+        let call = format!("{}({})", signature.name, args.join(", "));
+        match output_param {
+            Some(idx) => source.push_str(&format!(
+                "\t\t{}\n\t\tfmt.Println(\"\\n{}\" + lcFormat(a{}))\n",
+                call, OUTPUT_MARKER, idx
+            )),
+            None => source.push_str(&format!(
+                "\t\tfmt.Println(\"\\n{}\" + lcFormat({}))\n",
+                OUTPUT_MARKER, call
+            )),
+        }
+        source.push_str("\t}\n");
+    }
+    source.push_str("}\n");
+    Ok(source)
+}
+
+/// Whether `code` refers to `package.` outside of a longer identifier
+fn uses_package(code: &str, package: &str) -> bool {
+    let pattern = format!("{}.", package);
+    code.match_indices(&pattern).any(|(idx, _)| {
+        !code[..idx]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+    })
+}
+
+fn type_name(param_type: &ParamType) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Integer => "int".to_string(),
+        ParamType::Long => "int64".to_string(),
+        ParamType::Double => "float64".to_string(),
+        ParamType::Boolean => "bool".to_string(),
+        ParamType::String => "string".to_string(),
+        ParamType::Character => "byte".to_string(),
+        ParamType::Array(inner) | ParamType::List(inner) => format!("[]{}", type_name(inner)?),
+        ParamType::ListNode => "*ListNode".to_string(),
+        ParamType::TreeNode => "*TreeNode".to_string(),
+        ParamType::Void => bail!("Arguments can't be void"),
+    })
+}
+
+fn literal(param_type: &ParamType, value: &Value) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Integer | ParamType::Long => expect_i64(value)?.to_string(),
+        ParamType::Double => float_literal(expect_f64(value)?),
+        ParamType::Boolean => expect_bool(value)?.to_string(),
+        ParamType::String => format!("\"{}\"", escape(expect_str(value)?, '"')),
+        ParamType::Character => format!("'{}'", escape(&expect_char(value)?.to_string(), '\'')),
+        ParamType::Array(inner) | ParamType::List(inner) => format!(
+            "{}{{{}}}",
+            type_name(param_type)?,
+            expect_array(value)?
+                .iter()
+                .map(|value| literal(inner, value))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        ParamType::ListNode => format!(
+            "lcList([]int{{{}}})",
+            node_values(value)?
+                .iter()
+                .map(|val| val.map(|val| val.to_string()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::TreeNode => format!(
+            "lcTree([]interface{{}}{{{}}})",
+            node_values(value)?
+                .iter()
+                .map(|val| match val {
+                    Some(val) => val.to_string(),
+                    None => "nil".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::Void => bail!("Arguments can't be void"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal_of(param_type: &str, value: &str) -> Result<String> {
+        literal(
+            &param_type.parse().unwrap(),
+            &serde_json::from_str(value).unwrap(),
+        )
+    }
+
+    #[test]
+    fn writes_go_literals() {
+        assert_eq!(
+            literal_of("integer[][]", "[[1,-2],[]]").unwrap(),
+            "[][]int{[]int{1, -2}, []int{}}"
+        );
+        assert_eq!(
+            literal_of("list<string>", r#"["a\"b\n", ""]"#).unwrap(),
+            r#"[]string{"a\"b\n", ""}"#
+        );
+        assert_eq!(literal_of("long", "-3000000000").unwrap(), "-3000000000");
+        assert_eq!(
+            literal_of("character[]", r#"["a"]"#).unwrap(),
+            "[]byte{'a'}"
+        );
+        assert_eq!(
+            literal_of("TreeNode", "[1,null,-3]").unwrap(),
+            "lcTree([]interface{}{1, nil, -3})"
+        );
+        assert_eq!(
+            literal_of("ListNode", "[-1,2]").unwrap(),
+            "lcList([]int{-1, 2})"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use super::signature::{node_values, ParamType, Signature};
+use super::*;
+
+pub(super) const TOOLCHAIN: Toolchain = Toolchain {
+    source: "Main.java",
+    build: Some(&["javac", "-encoding", "UTF-8", "-d", "{dir}", "{src}"]),
+    run: &["java", "-cp", "{dir}", "Main"],
+};
+
+const PRELUDE: &str =
+    "import java.util.*;\nimport java.util.function.*;\nimport java.util.stream.*;\n";
+
+const LIST_NODE: &str = r#"
+class ListNode {
+    int val;
+    ListNode next;
+    ListNode() {}
+    ListNode(int val) { this.val = val; }
+    ListNode(int val, ListNode next) { this.val = val; this.next = next; }
+}
+"#;
+
+const TREE_NODE: &str = r#"
+class TreeNode {
+    int val;
+    TreeNode left;
+    TreeNode right;
+    TreeNode() {}
+    TreeNode(int val) { this.val = val; }
+    TreeNode(int val, TreeNode left, TreeNode right) {
+        this.val = val;
+        this.left = left;
+        this.right = right;
+    }
+}
+"#;
+
+const HELPERS: &str = r#"
+    static String format(Object value) {
+        if (value == null) return "null";
+        if (value instanceof Double) return String.format(Locale.ROOT, "%.5f", (Double) value);
+        if (value instanceof String || value instanceof Character) {
+            String s = String.valueOf(value);
+            return "\"" + s.replace("\\", "\\\\").replace("\"", "\\\"") + "\"";
+        }
+        if (value.getClass().isArray()) {
+            StringJoiner items = new StringJoiner(",", "[", "]");
+            for (int i = 0; i < java.lang.reflect.Array.getLength(value); i++) {
+                items.add(format(java.lang.reflect.Array.get(value, i)));
+            }
+            return items.toString();
+        }
+        if (value instanceof Iterable) {
+            StringJoiner items = new StringJoiner(",", "[", "]");
+            for (Object item : (Iterable<?>) value) items.add(format(item));
+            return items.toString();
+        }
+        if (value instanceof ListNode) return format((ListNode) value);
+        if (value instanceof TreeNode) return format((TreeNode) value);
+        return String.valueOf(value);
+    }
+
+    static String format(ListNode head) {
+        StringJoiner items = new StringJoiner(",", "[", "]");
+        for (ListNode node = head; node != null; node = node.next) {
+            items.add(String.valueOf(node.val));
+        }
+        return items.toString();
+    }
+
+    static String format(TreeNode root) {
+        List<String> items = new ArrayList<>();
+        List<TreeNode> level = new ArrayList<>();
+        level.add(root);
+        while (!level.isEmpty()) {
+            List<TreeNode> next = new ArrayList<>();
+            for (TreeNode node : level) {
+                if (node == null) {
+                    items.add("null");
+                } else {
+                    items.add(String.valueOf(node.val));
+                    next.add(node.left);
+                    next.add(node.right);
+                }
+            }
+            level = next;
+        }
+        while (!items.isEmpty() && items.get(items.size() - 1).equals("null")) {
+            items.remove(items.size() - 1);
+        }
+        return "[" + String.join(",", items) + "]";
+    }
+
+    static ListNode list(int[] values) {
+        ListNode head = null;
+        for (int i = values.length - 1; i >= 0; i--) head = new ListNode(values[i], head);
+        return head;
+    }
+
+    static TreeNode tree(Integer[] values) {
+        if (values.length == 0 || values[0] == null) return null;
+        TreeNode root = new TreeNode(values[0]);
+        Deque<TreeNode> queue = new ArrayDeque<>();
+        queue.add(root);
+        int i = 1;
+        while (!queue.isEmpty() && i < values.length) {
+            TreeNode node = queue.poll();
+            if (i < values.length && values[i] != null) {
+                node.left = new TreeNode(values[i]);
+                queue.add(node.left);
+            }
+            i++;
+            if (i < values.length && values[i] != null) {
+                node.right = new TreeNode(values[i]);
+                queue.add(node.right);
+            }
+            i++;
+        }
+        return root;
+    }
+"#;
+
+pub(super) fn generate(signature: &Signature, code: &str, cases: &[Vec<Value>]) -> Result<String> {
+    // imports are only allowed at the top of the file
+    let (imports, code): (Vec<&str>, Vec<&str>) = code
+        .lines()
+        .partition(|line| line.trim_start().starts_with("import "));
+
+    let mut source = String::from(PRELUDE);
+    source.push_str(&imports.join("\n"));
+    source.push('\n');
+    let code = code.join("\n");
+    if !defines(&code, &["class ListNode", "public class ListNode"]) {
+        source.push_str(LIST_NODE);
+    }
+    if !defines(&code, &["class TreeNode", "public class TreeNode"]) {
+        source.push_str(TREE_NODE);
+    }
+    source.push('\n');
+    source.push_str(&code);
+    source.push_str("\n\npublic class Main {");
+    source.push_str(HELPERS);
+    source.push_str("\n    public static void main(String[] args) {\n");
+
+    let output_param = signature.output_param();
+    for case in cases {
+        source.push_str("        {\n            Solution solution = new Solution();\n");
+        let mut args = Vec::with_capacity(case.len());
+        for (i, (param, value)) in signature.params.iter().zip(case).enumerate() {
+            source.push_str(&format!(
+                "            {} a{} = {};\n",
+                type_name(&param.param_type, false)?,
+                i,
+                literal(&param.param_type, value)?
+            ));
+            args.push(format!("a{}", i));
+        }
+        let call = format!("solution.{}({})", signature.name, args.join(", "));
+        match output_param {
+            Some(idx) => source.push_str(&format!(
+                "            {};\n            System.out.println(\"\\n{}\" + format(a{}));\n",
+                call, OUTPUT_MARKER, idx
+            )),
+            None => source.push_str(&format!(
+                "            System.out.println(\"\\n{}\" + format({}));\n",
+                OUTPUT_MARKER, call
+            )),
+        }
+        source.push_str("        }\n");
+    }
+    source.push_str("    }\n}\n");
+    Ok(source)
+}
+
+fn type_name(param_type: &ParamType, boxed: bool) -> Result<String> {
+    Ok(match (param_type, boxed) {
+        (ParamType::Integer, false) => "int".to_string(),
+        (ParamType::Integer, true) => "Integer".to_string(),
+        (ParamType::Long, false) => "long".to_string(),
+        (ParamType::Long, true) => "Long".to_string(),
+        (ParamType::Double, false) => "double".to_string(),
+        (ParamType::Double, true) => "Double".to_string(),
+        (ParamType::Boolean, false) => "boolean".to_string(),
+        (ParamType::Boolean, true) => "Boolean".to_string(),
+        (ParamType::Character, false) => "char".to_string(),
+        (ParamType::Character, true) => "Character".to_string(),
+        (ParamType::String, _) => "String".to_string(),
+        (ParamType::Array(inner), _) => format!("{}[]", type_name(inner, false)?),
+        (ParamType::List(inner), _) => format!("List<{}>", type_name(inner, true)?),
+        (ParamType::ListNode, _) => "ListNode".to_string(),
+        (ParamType::TreeNode, _) => "TreeNode".to_string(),
+        (ParamType::Void, _) => bail!("Arguments can't be void"),
+    })
+}
+
+fn literal(param_type: &ParamType, value: &Value) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Array(_) => format!(
+            "new {}{}",
+            type_name(param_type, false)?,
+            array_items(param_type, value)?
+        ),
+        ParamType::List(inner) => format!(
+            "new ArrayList<>(Arrays.asList({}))",
+            expect_array(value)?
+                .iter()
+                .map(|value| literal(inner, value))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        ParamType::ListNode => format!(
+            "list(new int[]{{{}}})",
+            node_values(value)?
+                .iter()
+                .map(|val| val.map(|val| val.to_string()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::TreeNode => format!(
+            "tree(new Integer[]{{{}}})",
+            node_values(value)?
+                .iter()
+                .map(|val| match val {
+                    Some(val) => val.to_string(),
+                    None => "null".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        scalar => scalar_literal(scalar, value)?,
+    })
+}
+
+/// Array initializer, nested arrays only need braces
+fn array_items(param_type: &ParamType, value: &Value) -> Result<String> {
+    match param_type {
+        ParamType::Array(inner) => Ok(format!(
+            "{{{}}}",
+            expect_array(value)?
+                .iter()
+                .map(|value| array_items(inner, value))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        )),
+        other => literal(other, value),
+    }
+}
+
+fn scalar_literal(param_type: &ParamType, value: &Value) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Integer => expect_i32(value)?.to_string(),
+        ParamType::Long => format!("{}L", expect_i64(value)?),
+        ParamType::Double => float_literal(expect_f64(value)?),
+        ParamType::Boolean => expect_bool(value)?.to_string(),
+        ParamType::String => format!("\"{}\"", escape(expect_str(value)?, '"')),
+        ParamType::Character => format!("'{}'", escape(&expect_char(value)?.to_string(), '\'')),
+        other => bail!("{:?} is not a scalar type", other),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal_of(param_type: &str, value: &str) -> Result<String> {
+        literal(
+            &param_type.parse().unwrap(),
+            &serde_json::from_str(value).unwrap(),
+        )
+    }
+
+    #[test]
+    fn writes_java_literals() {
+        assert_eq!(
+            literal_of("integer[][]", "[[1,-2],[]]").unwrap(),
+            "new int[][]{{1, -2}, {}}"
+        );
+        assert_eq!(
+            literal_of("list<list<integer>>", "[[1],[-2]]").unwrap(),
+            "new ArrayList<>(Arrays.asList(new ArrayList<>(Arrays.asList(1)), \
+             new ArrayList<>(Arrays.asList(-2))))"
+        );
+        assert_eq!(
+            literal_of("string[]", r#"["a\"b\n", ""]"#).unwrap(),
+            r#"new String[]{"a\"b\n", ""}"#
+        );
+        assert_eq!(literal_of("long", "-3000000000").unwrap(), "-3000000000L");
+        assert_eq!(
+            literal_of("TreeNode", "[1,null,-3]").unwrap(),
+            "tree(new Integer[]{1, null, -3})"
+        );
+        assert_eq!(
+            literal_of("ListNode", "[-1,2]").unwrap(),
+            "list(new int[]{-1, 2})"
+        );
+    }
+
+    #[test]
+    fn rejects_ints_out_of_range() {
+        assert!(literal_of("integer", "3000000000").is_err());
+    }
+}
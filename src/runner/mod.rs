@@ -0,0 +1,522 @@
+//! Compiles and runs solutions locally against testcases
+//!
+//! A harness is generated around the solution which constructs the arguments
+//! of every testcase as literals, calls the solution and prints the answer in
+//! the format used by LeetCode, so answers can be compared with the expected ones.
+
+use std::{
+    fmt,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::file_parser::{codefile::CodeFile, language::Language};
+use crate::output::Verdict;
+
+use colored::Colorize;
+use eyre::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+pub(crate) mod bash;
+mod cpp;
+mod golang;
+mod java;
+mod python;
+mod rust;
+pub(crate) mod signature;
+pub(crate) mod sql;
+
+use signature::{answers_match, parse_testcases, Signature};
+
+/// Prefix of the lines printed by the harness which hold an answer
+const OUTPUT_MARKER: &str = "#LCOUT ";
+
+/// Commands to build and run a harness, `{dir}`, `{src}` and `{bin}`
+/// are replaced by the working directory, source and binary paths
+pub(crate) struct Toolchain {
+    pub(crate) source: &'static str,
+    pub(crate) build: Option<&'static [&'static str]>,
+    pub(crate) run: &'static [&'static str],
+}
+
+#[derive(Serialize)]
+pub(crate) struct CaseOutput {
+    pub(crate) answer: String,
+    pub(crate) std_output: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum LocalError {
+    Compile {
+        message: String,
+    },
+    /// `testcase` is the 1-based index of the failing testcase
+    Runtime {
+        testcase: usize,
+        message: String,
+    },
+}
+
+#[derive(Serialize)]
+pub(crate) struct LocalRun {
+    pub(crate) inputs: Vec<Vec<Value>>,
+    pub(crate) outputs: Vec<CaseOutput>,
+    /// Expected answers, testcases without one are not judged
+    pub(crate) expected: Vec<Option<String>>,
+    pub(crate) error: Option<LocalError>,
+}
+
+pub(crate) fn is_supported(language: Language) -> bool {
+    toolchain(language).is_some() || language.is_database() || language == Language::Bash
+}
+
+/// Toolchain of the language, with the commands of the `languages` config
+/// taking precedence over the builtin ones
+fn toolchain(language: Language) -> Option<Toolchain> {
+    let builtin = match language {
+        Language::Rust => Some(rust::TOOLCHAIN),
+        Language::Python3 => Some(python::TOOLCHAIN),
+        Language::Cpp => Some(cpp::TOOLCHAIN),
+        Language::Java => Some(java::TOOLCHAIN),
+        Language::Go => Some(golang::TOOLCHAIN),
+        _ => None,
+    };
+    let Some(commands) = language.local_commands() else {
+        return builtin;
+    };
+    match builtin {
+        Some(builtin) => Some(Toolchain {
+            source: builtin.source,
+            build: commands.build.or(builtin.build),
+            run: commands.run.unwrap_or(builtin.run),
+        }),
+        None => Some(Toolchain {
+            source: commands.source,
+            build: commands.build,
+            run: commands.run?,
+        }),
+    }
+}
+
+/// Whether a harness is generated for the language, solutions of the
+/// others read the arguments of a testcase on stdin and print the answer
+fn has_harness(language: Language) -> bool {
+    matches!(
+        language,
+        Language::Rust | Language::Python3 | Language::Cpp | Language::Java | Language::Go
+    )
+}
+
+fn generate(
+    language: Language,
+    signature: &Signature,
+    code: &str,
+    cases: &[Vec<Value>],
+) -> Result<String> {
+    match language {
+        Language::Rust => rust::generate(signature, code, cases),
+        Language::Python3 => python::generate(signature, code, cases),
+        Language::Cpp => cpp::generate(signature, code, cases),
+        Language::Java => java::generate(signature, code, cases),
+        Language::Go => golang::generate(signature, code, cases),
+        other => bail!("{} is not supported by the local runner", other),
+    }
+}
+
+/// Runs `code_file` against `data_input`, formatted like LeetCode testcases,
+/// using the signature described by the question's `meta_data`
+pub(crate) fn run(
+    code_file: &CodeFile,
+    meta_data: &str,
+    data_input: &str,
+    expected: Vec<Option<String>>,
+) -> Result<LocalRun> {
+    let toolchain = toolchain(code_file.language).ok_or_else(|| {
+        eyre::eyre!(
+            "{} is not supported by the local runner",
+            code_file.language
+        )
+    })?;
+    let signature: Signature = meta_data.parse()?;
+    let inputs = parse_testcases(&signature, data_input)?;
+    if !has_harness(code_file.language) {
+        return run_plain(code_file, &toolchain, inputs, expected);
+    }
+    let source = generate(code_file.language, &signature, &code_file.code, &inputs)?;
+
+    // removed when dropped at the end of the run
+    let work_dir = work_dir(code_file)?;
+    let dir = work_dir.path();
+    let src = dir.join(toolchain.source);
+    let bin = dir.join(format!("main{}", std::env::consts::EXE_SUFFIX));
+    std::fs::write(&src, source)?;
+
+    if let Some(message) = build(&toolchain, dir, &src, &bin)? {
+        return Ok(LocalRun {
+            inputs,
+            outputs: Vec::new(),
+            expected,
+            error: Some(LocalError::Compile { message }),
+        });
+    }
+
+    let output = command(toolchain.run, dir, &src, &bin)?
+        .output()
+        .wrap_err_with(|| format!("Failed to execute {}", toolchain.run[0]))?;
+
+    let mut outputs = Vec::with_capacity(inputs.len());
+    let mut std_output = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        match line.strip_prefix(OUTPUT_MARKER) {
+            Some(answer) => outputs.push(CaseOutput {
+                answer: answer.to_string(),
+                std_output: std_output.join("\n").trim_end().to_string(),
+            }),
+            None => {
+                std_output.push(line.to_string());
+                continue;
+            }
+        }
+        std_output.clear();
+    }
+
+    let error = if output.status.success() && outputs.len() == inputs.len() {
+        None
+    } else {
+        Some(LocalError::Runtime {
+            testcase: outputs.len() + 1,
+            message: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    };
+    Ok(LocalRun {
+        inputs,
+        outputs,
+        expected,
+        error,
+    })
+}
+
+impl LocalRun {
+    fn is_case_correct(&self, idx: usize) -> Option<bool> {
+        let output = self.outputs.get(idx)?;
+        let expected = self.expected.get(idx)?.as_ref()?;
+        Some(answers_match(&output.answer, expected))
+    }
+
+    pub(crate) fn is_correct(&self) -> bool {
+        self.error.is_none()
+            && (0..self.outputs.len()).all(|idx| self.is_case_correct(idx).unwrap_or(true))
+    }
+
+    pub(crate) fn verdict(&self) -> Verdict {
+        match self.error {
+            Some(LocalError::Compile { .. }) => Verdict::CompileError,
+            Some(LocalError::Runtime { .. }) => Verdict::RuntimeError,
+            None if self.is_correct() => Verdict::Accepted,
+            None => Verdict::WrongAnswer,
+        }
+    }
+}
+
+impl fmt::Display for LocalRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seperator = "-------------------------------".yellow();
+        let judged = (0..self.outputs.len())
+            .filter_map(|idx| self.is_case_correct(idx))
+            .collect::<Vec<_>>();
+        let passed = judged.iter().filter(|&&correct| correct).count();
+        if self.is_correct() {
+            writeln!(f, "{}\n", "Local testcase execution success".green().bold())?;
+        } else if self.error.is_none() {
+            writeln!(
+                f,
+                "{}\n",
+                format!("Testcase {}/{} testcase passed", passed, judged.len())
+                    .red()
+                    .bold()
+            )?;
+        }
+
+        for (idx, output) in self.outputs.iter().enumerate() {
+            let status = match self.is_case_correct(idx) {
+                Some(true) => format!("Testcase {} execution success", idx + 1).green(),
+                Some(false) => format!("Testcase {} execution failed", idx + 1).red(),
+                None => format!("Testcase {} executed, no expected answer", idx + 1).yellow(),
+            };
+            let input = self.inputs[idx]
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "{seperator}\n{}\n{seperator}", status)?;
+            writeln!(f, "{:10}: {}", "Input", input)?;
+            writeln!(f, "{:10}: {}", "Output", output.answer)?;
+            if let Some(Some(expected)) = self.expected.get(idx) {
+                writeln!(f, "{:10}: {}", "Expected", expected)?;
+            }
+            if !output.std_output.is_empty() {
+                writeln!(f, "\nStd Output :\n{}", output.std_output)?;
+            }
+        }
+
+        match &self.error {
+            Some(LocalError::Compile { message }) => write!(
+                f,
+                "{}\n{seperator}\n{}",
+                "Compilation Error!".red().bold(),
+                message.trim_end()
+            ),
+            Some(LocalError::Runtime { testcase, message }) => write!(
+                f,
+                "{}\nTestcase {} failed during execution!\n{seperator}\n{}",
+                "Runtime Error!".red().bold(),
+                format!("{}", testcase).red(),
+                message.trim_end()
+            ),
+            None => write!(
+                f,
+                "{seperator}\n{:10}: {}",
+                "Status",
+                if self.is_correct() {
+                    "Testcase execution success".green().italic()
+                } else {
+                    "Testcase execution failed".yellow().italic()
+                }
+            ),
+        }
+    }
+}
+
+/// Runs the solution of a language without harness once per testcase,
+/// with the arguments as JSON on stdin, one per line. The answer is what
+/// the solution prints on stdout
+fn run_plain(
+    code_file: &CodeFile,
+    toolchain: &Toolchain,
+    inputs: Vec<Vec<Value>>,
+    expected: Vec<Option<String>>,
+) -> Result<LocalRun> {
+    let work_dir = work_dir(code_file)?;
+    let dir = work_dir.path();
+    let src = dir.join(toolchain.source);
+    let bin = dir.join(format!("main{}", std::env::consts::EXE_SUFFIX));
+    std::fs::write(&src, &code_file.code)?;
+
+    if let Some(message) = build(toolchain, dir, &src, &bin)? {
+        return Ok(LocalRun {
+            inputs,
+            outputs: Vec::new(),
+            expected,
+            error: Some(LocalError::Compile { message }),
+        });
+    }
+
+    let mut outputs = Vec::with_capacity(inputs.len());
+    let mut error = None;
+    for (idx, args) in inputs.iter().enumerate() {
+        let mut child = command(toolchain.run, dir, &src, &bin)?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .wrap_err_with(|| format!("Failed to execute {}", toolchain.run[0]))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let lines: String = args.iter().map(|arg| format!("{}\n", arg)).collect();
+            // the solution may exit without reading its input
+            let _ = stdin.write_all(lines.as_bytes());
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            error = Some(LocalError::Runtime {
+                testcase: idx + 1,
+                message: String::from_utf8_lossy(&output.stderr).to_string(),
+            });
+            break;
+        }
+        outputs.push(CaseOutput {
+            answer: String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string(),
+            std_output: String::from_utf8_lossy(&output.stderr)
+                .trim_end()
+                .to_string(),
+        });
+    }
+    Ok(LocalRun {
+        inputs,
+        outputs,
+        expected,
+        error,
+    })
+}
+
+/// Private directory where a solution is built and run. A shared one with
+/// a predictable path could be planted by another user, and concurrent
+/// runs would overwrite each other's files
+pub(super) fn work_dir(code_file: &CodeFile) -> Result<tempfile::TempDir> {
+    tempfile::Builder::new()
+        .prefix(&format!(
+            "leetcode-runner-{}-{}-",
+            code_file.question_title, code_file.language
+        ))
+        .tempdir()
+        .wrap_err("Failed to create the working directory of the run")
+}
+
+/// Runs the build command of `toolchain` if any, the compiler errors
+/// are returned when it fails
+fn build(toolchain: &Toolchain, dir: &Path, src: &Path, bin: &Path) -> Result<Option<String>> {
+    let Some(build) = toolchain.build else {
+        return Ok(None);
+    };
+    let output = command(build, dir, src, bin)?
+        .output()
+        .wrap_err_with(|| format!("Failed to execute {}", build[0]))?;
+    if output.status.success() {
+        Ok(None)
+    } else {
+        Ok(Some(String::from_utf8_lossy(&output.stderr).to_string()))
+    }
+}
+
+fn command(args: &[&str], dir: &Path, src: &Path, bin: &Path) -> Result<Command> {
+    let expand = |arg: &str| -> PathBuf {
+        arg.replace("{dir}", &dir.to_string_lossy())
+            .replace("{src}", &src.to_string_lossy())
+            .replace("{bin}", &bin.to_string_lossy())
+            .into()
+    };
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| eyre::eyre!("Empty toolchain command"))?;
+    let mut command = Command::new(expand(program));
+    command
+        .args(args.iter().map(|arg| expand(arg)))
+        .current_dir(dir);
+    Ok(command)
+}
+
+/// Whether `code` defines an item, ignoring commented out definitions
+fn defines(code: &str, definitions: &[&str]) -> bool {
+    code.lines().any(|line| {
+        let line = line.trim_start();
+        definitions
+            .iter()
+            .any(|definition| line.starts_with(definition))
+    })
+}
+
+/// Escapes a string for C-like string and character literals
+fn escape(s: &str, quote: char) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn expect_i64(value: &Value) -> Result<i64> {
+    value
+        .as_i64()
+        .ok_or_else(|| eyre::eyre!("Expected an integer, found {}", value))
+}
+
+/// `int` of every language but Python and Go, a testcase out of its range
+/// wouldn't compile or would silently wrap
+fn expect_i32(value: &Value) -> Result<i32> {
+    i32::try_from(expect_i64(value)?).map_err(|_| {
+        eyre::eyre!(
+            "Expected a 32-bit integer, found {} which is out of range",
+            value
+        )
+    })
+}
+
+fn expect_f64(value: &Value) -> Result<f64> {
+    value
+        .as_f64()
+        .ok_or_else(|| eyre::eyre!("Expected a number, found {}", value))
+}
+
+fn expect_bool(value: &Value) -> Result<bool> {
+    value
+        .as_bool()
+        .ok_or_else(|| eyre::eyre!("Expected a boolean, found {}", value))
+}
+
+fn expect_str(value: &Value) -> Result<&str> {
+    value
+        .as_str()
+        .ok_or_else(|| eyre::eyre!("Expected a string, found {}", value))
+}
+
+fn expect_char(value: &Value) -> Result<char> {
+    let s = expect_str(value)?;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => bail!("Expected a character, found {}", value),
+    }
+}
+
+fn expect_array(value: &Value) -> Result<&Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| eyre::eyre!("Expected an array, found {}", value))
+}
+
+/// Formats a float the way `{:?}` does, which is a valid literal in every target language
+fn float_literal(value: f64) -> String {
+    format!("{:?}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_the_range_of_ints() {
+        assert_eq!(expect_i32(&Value::from(i32::MAX)).unwrap(), i32::MAX);
+        assert_eq!(expect_i32(&Value::from(i32::MIN)).unwrap(), i32::MIN);
+        assert!(expect_i32(&Value::from(i64::from(i32::MAX) + 1)).is_err());
+        assert!(expect_i32(&Value::from(1.5)).is_err());
+        assert_eq!(
+            expect_i64(&Value::from(-3_000_000_000i64)).unwrap(),
+            -3_000_000_000
+        );
+    }
+
+    #[test]
+    fn escapes_string_literals() {
+        assert_eq!(escape("a\\b\t\"c'\n", '"'), r#"a\\b\t\"c'\n"#);
+        assert_eq!(escape("'", '\''), r"\'");
+        assert_eq!(escape("\u{1}", '"'), r"\001");
+    }
+
+    #[test]
+    fn formats_float_literals() {
+        assert_eq!(float_literal(1.0), "1.0");
+        assert_eq!(float_literal(-2.5), "-2.5");
+    }
+
+    #[test]
+    fn finds_definitions_outside_comments() {
+        let code = "// struct TreeNode {}\n  struct ListNode {\n}";
+        assert!(defines(code, &["struct ListNode"]));
+        assert!(!defines(code, &["struct TreeNode"]));
+    }
+}
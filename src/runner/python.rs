@@ -0,0 +1,223 @@
+use super::signature::{node_values, ParamType, Signature};
+use super::*;
+
+pub(super) const TOOLCHAIN: Toolchain = Toolchain {
+    source: "main.py",
+    build: None,
+    run: &["python3", "{src}"],
+};
+
+const PRELUDE: &str = r#"from typing import *
+import bisect
+import collections
+import functools
+import heapq
+import itertools
+import math
+import string
+from collections import Counter, defaultdict, deque
+from functools import cache, lru_cache
+from heapq import heapify, heappop, heappush
+"#;
+
+const LIST_NODE: &str = r#"
+class ListNode:
+    def __init__(self, val=0, next=None):
+        self.val = val
+        self.next = next
+"#;
+
+const TREE_NODE: &str = r#"
+class TreeNode:
+    def __init__(self, val=0, left=None, right=None):
+        self.val = val
+        self.left = left
+        self.right = right
+"#;
+
+const HELPERS: &str = r#"
+def lc_format(value, node=False):
+    if value is None:
+        return "[]" if node else "null"
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, float):
+        return "%.5f" % value
+    if isinstance(value, str):
+        return '"' + value.replace("\\", "\\\\").replace('"', '\\"') + '"'
+    if isinstance(value, (list, tuple)):
+        return "[" + ",".join(lc_format(item) for item in value) + "]"
+    if isinstance(value, ListNode):
+        items = []
+        while value:
+            items.append(str(value.val))
+            value = value.next
+        return "[" + ",".join(items) + "]"
+    if isinstance(value, TreeNode):
+        items, queue = [], collections.deque([value])
+        while queue:
+            node = queue.popleft()
+            if node is None:
+                items.append("null")
+            else:
+                items.append(str(node.val))
+                queue.append(node.left)
+                queue.append(node.right)
+        while items and items[-1] == "null":
+            items.pop()
+        return "[" + ",".join(items) + "]"
+    return str(value)
+
+
+def lc_list(values):
+    head = None
+    for val in reversed(values):
+        head = ListNode(val, head)
+    return head
+
+
+def lc_tree(values):
+    if not values or values[0] is None:
+        return None
+    values = iter(values)
+    root = TreeNode(next(values))
+    queue = collections.deque([root])
+    while queue:
+        node = queue.popleft()
+        val = next(values, "end")
+        if val == "end":
+            break
+        if val is not None:
+            node.left = TreeNode(val)
+            queue.append(node.left)
+        val = next(values, "end")
+        if val == "end":
+            break
+        if val is not None:
+            node.right = TreeNode(val)
+            queue.append(node.right)
+    return root
+"#;
+
+pub(super) fn generate(signature: &Signature, code: &str, cases: &[Vec<Value>]) -> Result<String> {
+    let mut source = String::from(PRELUDE);
+    if !defines(code, &["class ListNode"]) {
+        source.push_str(LIST_NODE);
+    }
+    if !defines(code, &["class TreeNode"]) {
+        source.push_str(TREE_NODE);
+    }
+    source.push_str(HELPERS);
+    source.push_str("\n\n");
+    source.push_str(code);
+    source.push_str("\n\n\nif __name__ == \"__main__\":\n");
+
+    let output_param = signature.output_param();
+    // empty lists and trees are `None` which is printed as an empty array
+    let answer_type = match output_param {
+        Some(idx) => &signature.params[idx].param_type,
+        None => &signature.return_type.return_type,
+    };
+    let node = match answer_type {
+        ParamType::ListNode | ParamType::TreeNode => "True",
+        _ => "False",
+    };
+    for case in cases {
+        let mut args = Vec::with_capacity(case.len());
+        for (i, (param, value)) in signature.params.iter().zip(case).enumerate() {
+            source.push_str(&format!(
+                "    a{} = {}\n",
+                i,
+                literal(&param.param_type, value)?
+            ));
+            args.push(format!("a{}", i));
+        }
+        let call = format!("Solution().{}({})", signature.name, args.join(", "));
+        match output_param {
+            Some(idx) => source.push_str(&format!(
+                "    {}\n    print(\"\\n{}\" + lc_format(a{}, {}), flush=True)\n",
+                call, OUTPUT_MARKER, idx, node
+            )),
+            None => source.push_str(&format!(
+                "    print(\"\\n{}\" + lc_format({}, {}), flush=True)\n",
+                OUTPUT_MARKER, call, node
+            )),
+        }
+    }
+    if cases.is_empty() {
+        source.push_str("    pass\n");
+    }
+    Ok(source)
+}
+
+fn literal(param_type: &ParamType, value: &Value) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Integer | ParamType::Long => expect_i64(value)?.to_string(),
+        ParamType::Double => float_literal(expect_f64(value)?),
+        ParamType::Boolean => if expect_bool(value)? { "True" } else { "False" }.to_string(),
+        ParamType::String => format!("\"{}\"", escape(expect_str(value)?, '"')),
+        ParamType::Character => format!("\"{}\"", escape(&expect_char(value)?.to_string(), '"')),
+        ParamType::Array(inner) | ParamType::List(inner) => format!(
+            "[{}]",
+            expect_array(value)?
+                .iter()
+                .map(|value| literal(inner, value))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        ParamType::ListNode | ParamType::TreeNode => format!(
+            "{}([{}])",
+            if *param_type == ParamType::ListNode {
+                "lc_list"
+            } else {
+                "lc_tree"
+            },
+            node_values(value)?
+                .iter()
+                .map(|val| match val {
+                    Some(val) => val.to_string(),
+                    None => "None".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::Void => bail!("Arguments can't be void"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal_of(param_type: &str, value: &str) -> Result<String> {
+        literal(
+            &param_type.parse().unwrap(),
+            &serde_json::from_str(value).unwrap(),
+        )
+    }
+
+    #[test]
+    fn writes_python_literals() {
+        assert_eq!(
+            literal_of("integer[][]", "[[1,-2],[]]").unwrap(),
+            "[[1, -2], []]"
+        );
+        assert_eq!(
+            literal_of("list<string>", r#"["a\"b\n", ""]"#).unwrap(),
+            r#"["a\"b\n", ""]"#
+        );
+        assert_eq!(literal_of("long", "-3000000000").unwrap(), "-3000000000");
+        assert_eq!(
+            literal_of("boolean[]", "[true,false]").unwrap(),
+            "[True, False]"
+        );
+        assert_eq!(
+            literal_of("TreeNode", "[1,null,-3]").unwrap(),
+            "lc_tree([1, None, -3])"
+        );
+        assert_eq!(
+            literal_of("ListNode", "[-1,2]").unwrap(),
+            "lc_list([-1, 2])"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use super::signature::{node_values, ParamType, Signature};
+use super::*;
+
+pub(super) const TOOLCHAIN: Toolchain = Toolchain {
+    source: "main.rs",
+    build: Some(&["rustc", "--edition", "2021", "-O", "-o", "{bin}", "{src}"]),
+    run: &["{bin}"],
+};
+
+const LIST_NODE: &str = r#"
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ListNode {
+    pub val: i32,
+    pub next: Option<Box<ListNode>>,
+}
+
+impl ListNode {
+    #[inline]
+    fn new(val: i32) -> Self {
+        ListNode { next: None, val }
+    }
+}
+"#;
+
+const TREE_NODE: &str = r#"
+#[derive(Debug, PartialEq, Eq)]
+pub struct TreeNode {
+    pub val: i32,
+    pub left: Option<std::rc::Rc<std::cell::RefCell<TreeNode>>>,
+    pub right: Option<std::rc::Rc<std::cell::RefCell<TreeNode>>>,
+}
+
+impl TreeNode {
+    #[inline]
+    pub fn new(val: i32) -> Self {
+        TreeNode {
+            val,
+            left: None,
+            right: None,
+        }
+    }
+}
+"#;
+
+const HELPERS: &str = r#"
+trait LcFormat {
+    fn lc_format(&self) -> String;
+}
+
+impl LcFormat for i32 {
+    fn lc_format(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl LcFormat for i64 {
+    fn lc_format(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl LcFormat for f64 {
+    fn lc_format(&self) -> String {
+        format!("{:.5}", self)
+    }
+}
+
+impl LcFormat for bool {
+    fn lc_format(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl LcFormat for char {
+    fn lc_format(&self) -> String {
+        format!("{:?}", self.to_string())
+    }
+}
+
+impl LcFormat for String {
+    fn lc_format(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl<T: LcFormat> LcFormat for Vec<T> {
+    fn lc_format(&self) -> String {
+        let items: Vec<String> = self.iter().map(|item| item.lc_format()).collect();
+        format!("[{}]", items.join(","))
+    }
+}
+
+impl<T: LcFormat> LcFormat for &mut T {
+    fn lc_format(&self) -> String {
+        (**self).lc_format()
+    }
+}
+
+impl LcFormat for Option<Box<ListNode>> {
+    fn lc_format(&self) -> String {
+        let mut items = Vec::new();
+        let mut node = self;
+        while let Some(inner) = node {
+            items.push(inner.val.to_string());
+            node = &inner.next;
+        }
+        format!("[{}]", items.join(","))
+    }
+}
+
+impl LcFormat for Option<std::rc::Rc<std::cell::RefCell<TreeNode>>> {
+    fn lc_format(&self) -> String {
+        let mut items = Vec::new();
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back(self.clone());
+        while let Some(node) = queue.pop_front() {
+            match node {
+                Some(node) => {
+                    let node = node.borrow();
+                    items.push(node.val.to_string());
+                    queue.push_back(node.left.clone());
+                    queue.push_back(node.right.clone());
+                }
+                None => items.push("null".to_string()),
+            }
+        }
+        while items.last().map(String::as_str) == Some("null") {
+            items.pop();
+        }
+        format!("[{}]", items.join(","))
+    }
+}
+
+fn lc_list(values: Vec<i32>) -> Option<Box<ListNode>> {
+    let mut head = None;
+    for val in values.into_iter().rev() {
+        let mut node = ListNode::new(val);
+        node.next = head;
+        head = Some(Box::new(node));
+    }
+    head
+}
+
+fn lc_tree(values: Vec<Option<i32>>) -> Option<std::rc::Rc<std::cell::RefCell<TreeNode>>> {
+    let new_node = |val: i32| std::rc::Rc::new(std::cell::RefCell::new(TreeNode::new(val)));
+    let mut values = values.into_iter();
+    let root = new_node(values.next().flatten()?);
+    let mut queue = std::collections::VecDeque::from([root.clone()]);
+    while let Some(node) = queue.pop_front() {
+        match values.next() {
+            Some(Some(val)) => {
+                let child = new_node(val);
+                node.borrow_mut().left = Some(child.clone());
+                queue.push_back(child);
+            }
+            Some(None) => {}
+            None => break,
+        }
+        match values.next() {
+            Some(Some(val)) => {
+                let child = new_node(val);
+                node.borrow_mut().right = Some(child.clone());
+                queue.push_back(child);
+            }
+            Some(None) => {}
+            None => break,
+        }
+    }
+    Some(root)
+}
+"#;
+
+pub(super) fn generate(signature: &Signature, code: &str, cases: &[Vec<Value>]) -> Result<String> {
+    let mut source = String::from("#![allow(unused, dead_code, non_snake_case)]\n");
+    if !defines(code, &["struct Solution", "pub struct Solution"]) {
+        source.push_str("struct Solution;\n");
+    }
+    if !defines(code, &["struct ListNode", "pub struct ListNode"]) {
+        source.push_str(LIST_NODE);
+    }
+    if !defines(code, &["struct TreeNode", "pub struct TreeNode"]) {
+        source.push_str(TREE_NODE);
+    }
+    source.push_str(HELPERS);
+    source.push('\n');
+    source.push_str(code);
+    source.push_str("\n\nfn main() {\n");
+
+    let output_param = signature.output_param();
+    let function = snake_case(&signature.name);
+    for case in cases {
+        source.push_str("    {\n");
+        let mut args = Vec::with_capacity(case.len());
+        for (i, (param, value)) in signature.params.iter().zip(case).enumerate() {
+            source.push_str(&format!(
+                "        let mut a{} = {};\n",
+                i,
+                literal(&param.param_type, value)?
+            ));
+            args.push(match output_param {
+                Some(idx) if idx == i => format!("&mut a{}", i),
+                _ => format!("a{}", i),
+            });
+        }
+        let call = format!("Solution::{}({})", function, args.join(", "));
+        match output_param {
+            Some(idx) => source.push_str(&format!(
+                "        {};\n        println!(\"\\n{}{{}}\", a{}.lc_format());\n",
+                call, OUTPUT_MARKER, idx
+            )),
+            None => source.push_str(&format!(
+                "        println!(\"\\n{}{{}}\", {}.lc_format());\n",
+                OUTPUT_MARKER, call
+            )),
+        }
+        source.push_str("    }\n");
+    }
+    source.push_str("}\n");
+    Ok(source)
+}
+
+/// LeetCode names rust methods in snake case, `twoSum` becomes `two_sum`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if !snake.is_empty() {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn literal(param_type: &ParamType, value: &Value) -> Result<String> {
+    Ok(match param_type {
+        ParamType::Integer => format!("{}i32", expect_i32(value)?),
+        ParamType::Long => format!("{}i64", expect_i64(value)?),
+        ParamType::Double => format!("{}f64", float_literal(expect_f64(value)?)),
+        ParamType::Boolean => expect_bool(value)?.to_string(),
+        ParamType::String => format!("String::from({:?})", expect_str(value)?),
+        ParamType::Character => format!("{:?}", expect_char(value)?),
+        ParamType::Array(inner) | ParamType::List(inner) => format!(
+            "vec![{}]",
+            expect_array(value)?
+                .iter()
+                .map(|value| literal(inner, value))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        ParamType::ListNode => format!(
+            "lc_list(vec![{}])",
+            node_values(value)?
+                .iter()
+                .map(|val| val.map(|val| val.to_string()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::TreeNode => format!(
+            "lc_tree(vec![{}])",
+            node_values(value)?
+                .iter()
+                .map(|val| match val {
+                    Some(val) => format!("Some({})", val),
+                    None => "None".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ParamType::Void => bail!("Arguments can't be void"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal_of(param_type: &str, value: &str) -> Result<String> {
+        literal(
+            &param_type.parse().unwrap(),
+            &serde_json::from_str(value).unwrap(),
+        )
+    }
+
+    #[test]
+    fn writes_rust_literals() {
+        assert_eq!(
+            literal_of("integer[][]", "[[1,-2],[]]").unwrap(),
+            "vec![vec![1i32, -2i32], vec![]]"
+        );
+        assert_eq!(
+            literal_of("list<string>", r#"["a\"b\n", ""]"#).unwrap(),
+            r#"vec![String::from("a\"b\n"), String::from("")]"#
+        );
+        assert_eq!(literal_of("long", "-3000000000").unwrap(), "-3000000000i64");
+        assert_eq!(literal_of("double", "-0.5").unwrap(), "-0.5f64");
+        assert_eq!(literal_of("character", r#""'""#).unwrap(), r"'\''");
+        assert_eq!(
+            literal_of("TreeNode", "[1,null,-3]").unwrap(),
+            "lc_tree(vec![Some(1), None, Some(-3)])"
+        );
+        assert_eq!(literal_of("ListNode", "[]").unwrap(), "lc_list(vec![])");
+    }
+
+    #[test]
+    fn rejects_ints_out_of_range() {
+        let err = literal_of("integer[]", "[1, 3000000000]").unwrap_err();
+        assert!(err.to_string().contains("32-bit"), "{}", err);
+        assert!(literal_of("ListNode", "[-3000000000]").is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use std::str::FromStr;
+
+use eyre::{bail, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Function signature of a question as described by its `metaData`
+#[derive(Debug, Deserialize)]
+pub(crate) struct Signature {
+    pub(crate) name: String,
+    pub(crate) params: Vec<Param>,
+    #[serde(rename = "return")]
+    pub(crate) return_type: Return,
+    /// Argument holding the answer of functions returning `void`
+    #[serde(default)]
+    pub(crate) output: Option<Output>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Output {
+    pub(crate) paramindex: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Param {
+    #[serde(rename = "type")]
+    pub(crate) param_type: ParamType,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Return {
+    #[serde(rename = "type")]
+    pub(crate) return_type: ParamType,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) enum ParamType {
+    Integer,
+    Long,
+    Double,
+    Boolean,
+    String,
+    Character,
+    /// `T[]`
+    Array(Box<ParamType>),
+    /// `list<T>`
+    List(Box<ParamType>),
+    ListNode,
+    TreeNode,
+    Void,
+}
+
+impl FromStr for Signature {
+    type Err = eyre::ErrReport;
+
+    fn from_str(meta_data: &str) -> std::result::Result<Self, Self::Err> {
+        let value: Value = serde_json::from_str(meta_data)
+            .map_err(|err| eyre::eyre!("Failed to parse question metadata: {}", err))?;
+        if value.get("classname").is_some() || value.get("systemdesign").is_some() {
+            bail!("Design problems are not supported by the local runner");
+        }
+        serde_json::from_value(value)
+            .map_err(|err| eyre::eyre!("Unsupported question signature: {}", err))
+    }
+}
+
+impl FromStr for ParamType {
+    type Err = eyre::ErrReport;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(inner) = s.strip_suffix("[]") {
+            return Ok(ParamType::Array(Box::new(inner.parse()?)));
+        }
+        if let Some(inner) = s.strip_prefix("list<").and_then(|s| s.strip_suffix('>')) {
+            return Ok(ParamType::List(Box::new(inner.parse()?)));
+        }
+        match s {
+            "integer" => Ok(ParamType::Integer),
+            "long" => Ok(ParamType::Long),
+            "double" => Ok(ParamType::Double),
+            "boolean" => Ok(ParamType::Boolean),
+            "string" => Ok(ParamType::String),
+            "character" => Ok(ParamType::Character),
+            "ListNode" => Ok(ParamType::ListNode),
+            "TreeNode" => Ok(ParamType::TreeNode),
+            "void" => Ok(ParamType::Void),
+            other => bail!("Type {} is not supported by the local runner", other),
+        }
+    }
+}
+
+impl TryFrom<String> for ParamType {
+    type Error = eyre::ErrReport;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Signature {
+    /// Index of the argument to print when the function returns `void`
+    pub(crate) fn output_param(&self) -> Option<usize> {
+        match self.return_type.return_type {
+            ParamType::Void => Some(self.output.as_ref().map_or(0, |out| out.paramindex)),
+            _ => None,
+        }
+    }
+}
+
+/// Splits testcase input into one set of argument values per testcase
+pub(crate) fn parse_testcases(signature: &Signature, data_input: &str) -> Result<Vec<Vec<Value>>> {
+    let lines = data_input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let params = signature.params.len().max(1);
+    if lines.len() % params != 0 {
+        bail!(
+            "Expected {} lines per testcase, found {} lines in total",
+            params,
+            lines.len()
+        );
+    }
+    lines
+        .chunks(params)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|line| {
+                    serde_json::from_str(line)
+                        .map_err(|err| eyre::eyre!("Invalid testcase input {}: {}", line, err))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Values of a serialized linked list or level order tree, nodes hold an `int`
+pub(crate) fn node_values(value: &Value) -> Result<Vec<Option<i32>>> {
+    value
+        .as_array()
+        .ok_or_else(|| eyre::eyre!("Expected an array, found {}", value))?
+        .iter()
+        .map(|value| match value {
+            Value::Null => Ok(None),
+            value => super::expect_i32(value).map(Some),
+        })
+        .collect()
+}
+
+/// Compares two answers, numbers are compared with a tolerance
+pub(crate) fn answers_match(output: &str, expected: &str) -> bool {
+    match (
+        serde_json::from_str::<Value>(output),
+        serde_json::from_str::<Value>(expected),
+    ) {
+        (Ok(output), Ok(expected)) => values_match(&output, &expected),
+        _ => output.trim() == expected.trim(),
+    }
+}
+
+fn values_match(output: &Value, expected: &Value) -> bool {
+    match (output, expected) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => (a - b).abs() <= 1e-5,
+                _ => false,
+            },
+        },
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| values_match(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| values_match(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_SUM: &str = r#"{
+        "name": "twoSum",
+        "params": [{"name": "nums", "type": "integer[]"}, {"name": "target", "type": "integer"}],
+        "return": {"type": "integer[]", "size": 2}
+    }"#;
+
+    #[test]
+    fn parses_question_metadata() {
+        let signature: Signature = TWO_SUM.parse().unwrap();
+        assert_eq!(signature.name, "twoSum");
+        let types: Vec<ParamType> = signature
+            .params
+            .iter()
+            .map(|param| param.param_type.clone())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ParamType::Array(Box::new(ParamType::Integer)),
+                ParamType::Integer
+            ]
+        );
+        assert_eq!(signature.output_param(), None);
+
+        let merge: Signature = r#"{
+            "name": "merge",
+            "params": [
+                {"name": "nums1", "type": "integer[]"}, {"type": "integer", "name": "m"},
+                {"type": "integer[]", "name": "nums2"}, {"type": "integer", "name": "n"}
+            ],
+            "return": {"type": "void"},
+            "output": {"paramindex": 0}
+        }"#
+        .parse()
+        .unwrap();
+        assert_eq!(merge.output_param(), Some(0));
+
+        assert!(
+            r#"{"classname": "LRUCache", "constructor": {"params": []}, "methods": []}"#
+                .parse::<Signature>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parses_nested_types() {
+        assert_eq!(
+            "list<list<string>>".parse::<ParamType>().unwrap(),
+            ParamType::List(Box::new(ParamType::List(Box::new(ParamType::String))))
+        );
+        assert_eq!(
+            "character[][]".parse::<ParamType>().unwrap(),
+            ParamType::Array(Box::new(ParamType::Array(Box::new(ParamType::Character))))
+        );
+        assert!("NestedInteger".parse::<ParamType>().is_err());
+        assert!("list<Node>".parse::<ParamType>().is_err());
+    }
+
+    #[test]
+    fn splits_testcases_by_parameter_count() {
+        let signature: Signature = TWO_SUM.parse().unwrap();
+        let cases = parse_testcases(&signature, "[2,7,11,15]\n9\n\n  [3,2,4]\n6\n").unwrap();
+        assert_eq!(
+            cases,
+            [
+                vec![serde_json::json!([2, 7, 11, 15]), serde_json::json!(9)],
+                vec![serde_json::json!([3, 2, 4]), serde_json::json!(6)],
+            ]
+        );
+        assert!(parse_testcases(&signature, "[1,2]\n3\n[4]").is_err());
+        assert!(parse_testcases(&signature, "[1,2\n3").is_err());
+    }
+
+    #[test]
+    fn node_values_hold_ints() {
+        assert_eq!(
+            node_values(&serde_json::json!([1, null, -3])).unwrap(),
+            [Some(1), None, Some(-3)]
+        );
+        assert!(node_values(&serde_json::json!([1, 3_000_000_000i64])).is_err());
+        assert!(node_values(&serde_json::json!({"val": 1})).is_err());
+    }
+
+    #[test]
+    fn matches_answers() {
+        assert!(answers_match("[0,1]", "[0, 1]"));
+        assert!(!answers_match("[1,0]", "[0,1]"));
+        assert!(!answers_match("[0,1]", "[0,1,2]"));
+        assert!(answers_match(r#"[["a","b"],[]]"#, r#"[["a", "b"], []]"#));
+        assert!(answers_match("2.00000", "2.000001"));
+        assert!(answers_match("1", "1.0"));
+        assert!(!answers_match("2.1", "2.0"));
+        assert!(!answers_match("1", "\"1\""));
+        assert!(answers_match("not json ", "not json"));
+    }
+}
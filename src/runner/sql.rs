@@ -0,0 +1,291 @@
+//! Runs the queries of database questions against an in-memory SQLite database
+//!
+//! The tables are created from the MySQL schema of the question and filled
+//! with the rows of each testcase. SQLite understands most of the MySQL used
+//! by solutions, which is enough to iterate before submitting to LeetCode.
+
+use std::collections::BTreeMap;
+
+use super::{CaseOutput, LocalError, LocalRun};
+use crate::file_parser::codefile::CodeFile;
+
+use eyre::{Context, Result};
+use rusqlite::{fallible_iterator::FallibleIterator, types::ValueRef, Batch, Connection};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Tables of a testcase, as in the inputs of database questions
+#[derive(Deserialize)]
+struct Tables {
+    headers: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    rows: BTreeMap<String, Vec<Vec<Value>>>,
+}
+
+/// Result of a query, as in the answers of database questions
+struct QueryResult {
+    headers: Vec<String>,
+    values: Vec<Vec<Value>>,
+}
+
+/// Runs the query of `code_file` once per testcase of `data_input`,
+/// which holds a JSON object of tables per line
+pub(crate) fn run(
+    code_file: &CodeFile,
+    schemas: &[String],
+    data_input: &str,
+    expected: Vec<Option<String>>,
+) -> Result<LocalRun> {
+    let mut inputs = Vec::new();
+    let mut cases = Vec::new();
+    for line in data_input.lines().filter(|line| !line.trim().is_empty()) {
+        let value: Value = serde_json::from_str(line)
+            .wrap_err_with(|| format!("Expected the tables of a testcase, found {}", line))?;
+        cases.push(Tables::deserialize(&value)?);
+        inputs.push(vec![value]);
+    }
+    let creates: Vec<String> = schemas
+        .iter()
+        .map(|statement| statement.trim())
+        .filter(|statement| starts_with_keyword(statement, "create"))
+        .map(to_sqlite)
+        .collect();
+    // SQLite has no `#` comments, which start the MySQL boilerplate
+    let code = code_file
+        .code
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    // rows are compared in order only when the query sorts them
+    let ordered = sorts_rows(&code);
+
+    let mut outputs = Vec::with_capacity(cases.len());
+    let mut error = None;
+    for (idx, tables) in cases.iter().enumerate() {
+        match query(&creates, tables, &code) {
+            Ok(result) => outputs.push(CaseOutput {
+                answer: result.to_json(ordered),
+                std_output: String::new(),
+            }),
+            Err(err) => {
+                error = Some(LocalError::Runtime {
+                    testcase: idx + 1,
+                    message: format!("{:#}", err),
+                });
+                break;
+            }
+        }
+    }
+    let expected = expected
+        .into_iter()
+        .map(|answer| answer.map(|answer| normalize_answer(&answer, ordered)))
+        .collect();
+    Ok(LocalRun {
+        inputs,
+        outputs,
+        expected,
+        error,
+    })
+}
+
+/// Runs `code` on a fresh database holding `tables`. Statements which don't
+/// return rows, like a `DELETE`, are answered with the table they changed
+fn query(creates: &[String], tables: &Tables, code: &str) -> Result<QueryResult> {
+    let conn = Connection::open_in_memory()?;
+    for create in creates {
+        conn.execute_batch(create)
+            .wrap_err_with(|| format!("Failed to create the table of {}", create))?;
+    }
+    for (table, columns) in &tables.headers {
+        // in case the schema has no statement for the table
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            quote(table),
+            columns
+                .iter()
+                .map(|c| quote(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))?;
+        let insert = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote(table),
+            columns
+                .iter()
+                .map(|c| quote(c))
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        let mut statement = conn.prepare(&insert)?;
+        for row in tables.rows.get(table).into_iter().flatten() {
+            statement
+                .execute(rusqlite::params_from_iter(row.iter().map(to_sql)))
+                .wrap_err_with(|| format!("Failed to insert {:?} into {}", row, table))?;
+        }
+    }
+
+    let mut result = None;
+    let mut executed = false;
+    let mut batch = Batch::new(&conn, code);
+    while let Some(mut statement) = batch.next()? {
+        executed = true;
+        if statement.column_count() == 0 {
+            statement.raw_execute()?;
+            continue;
+        }
+        let headers: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut values = Vec::new();
+        let mut rows = statement.raw_query();
+        while let Some(row) = rows.next()? {
+            values.push(
+                (0..headers.len())
+                    .map(|idx| row.get_ref(idx).map(to_json))
+                    .collect::<rusqlite::Result<_>>()?,
+            );
+        }
+        result = Some(QueryResult { headers, values });
+    }
+    if !executed {
+        eyre::bail!("The code file has no query");
+    }
+    match (result, tables.headers.keys().next()) {
+        (Some(result), _) => Ok(result),
+        (None, Some(table)) => select_all(&conn, table),
+        (None, None) => eyre::bail!("The query returned no rows"),
+    }
+}
+
+fn select_all(conn: &Connection, table: &str) -> Result<QueryResult> {
+    let mut statement = conn.prepare(&format!("SELECT * FROM {}", quote(table)))?;
+    let headers: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let values = statement
+        .query_map([], |row| {
+            (0..headers.len())
+                .map(|idx| row.get_ref(idx).map(to_json))
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(QueryResult { headers, values })
+}
+
+impl QueryResult {
+    fn to_json(&self, ordered: bool) -> String {
+        let mut values: Vec<Value> = self.values.iter().cloned().map(Value::Array).collect();
+        if !ordered {
+            sort_rows(&mut values);
+        }
+        json!({ "headers": self.headers, "values": values }).to_string()
+    }
+}
+
+/// Sorts the rows of an answer unless they are `ordered`, so that answers
+/// with the same rows compare equal
+fn normalize_answer(answer: &str, ordered: bool) -> String {
+    let Ok(mut value) = serde_json::from_str::<Value>(answer) else {
+        return answer.to_string();
+    };
+    if let (false, Some(Value::Array(rows))) = (ordered, value.get_mut("values")) {
+        sort_rows(rows);
+    }
+    value.to_string()
+}
+
+fn sort_rows(rows: &mut [Value]) {
+    // 2 and 2.0 sort alike
+    rows.sort_by_cached_key(|row| match row {
+        Value::Array(row) => row
+            .iter()
+            .map(|value| match value.as_f64() {
+                Some(number) if number.fract() == 0.0 => (number as i64).to_string(),
+                _ => value.to_string(),
+            })
+            .collect::<Vec<_>>(),
+        other => vec![other.to_string()],
+    });
+}
+
+fn to_sql(value: &Value) -> rusqlite::types::Value {
+    use rusqlite::types::Value as Sql;
+    match value {
+        Value::Null => Sql::Null,
+        Value::Bool(b) => Sql::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Sql::Integer(i),
+            None => Sql::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => Sql::Text(s.clone()),
+        other => Sql::Text(other.to_string()),
+    }
+}
+
+fn to_json(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => json!(i),
+        ValueRef::Real(f) => json!(f),
+        ValueRef::Text(text) | ValueRef::Blob(text) => {
+            Value::String(String::from_utf8_lossy(text).to_string())
+        }
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn starts_with_keyword(statement: &str, keyword: &str) -> bool {
+    statement
+        .get(..keyword.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(keyword))
+}
+
+/// Whether the rows of the answer are sorted, by an `ORDER BY` outside
+/// of the parentheses of subqueries and window functions
+fn sorts_rows(code: &str) -> bool {
+    let lower = code.to_ascii_lowercase();
+    lower.match_indices("order by").any(|(idx, _)| {
+        let before = &lower[..idx];
+        before.matches('(').count() == before.matches(')').count()
+    })
+}
+
+/// Rewrites the MySQL of a `CREATE TABLE` into SQLite, which has no
+/// `ENUM` and quotes names with double quotes
+fn to_sqlite(create: &str) -> String {
+    let mut sqlite = create.replace('`', "\"");
+    while let Some(range) = enum_type(&sqlite) {
+        sqlite.replace_range(range, "TEXT");
+    }
+    sqlite
+}
+
+/// Bytes of the first `ENUM(...)` column type
+fn enum_type(create: &str) -> Option<std::ops::Range<usize>> {
+    let lower = create.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(idx) = lower[from..].find("enum") {
+        let start = from + idx;
+        from = start + "enum".len();
+        let word_start = lower[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+        let args = lower[from..].trim_start();
+        if word_start && args.starts_with('(') {
+            let open = lower.len() - args.len();
+            let close = open + args.find(')')?;
+            return Some(start..close + 1);
+        }
+    }
+    None
+}
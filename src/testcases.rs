@@ -0,0 +1,317 @@
+//! Per-question testcase database
+//!
+//! Testcases are kept in `.leetcode/<slug>/cases.json`, next to the nearest
+//! `.leetcode` directory found in the current directory or its ancestors.
+//! Expected answers are captured from LeetCode whenever the testcases are run.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use eyre::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+const STORE_DIR: &str = ".leetcode";
+const CASES_FILE: &str = "cases.json";
+/// Tag of the testcases taken from the question's examples
+const EXAMPLE_TAG: &str = "example";
+/// Tag of the testcases a submission failed on
+const SUBMISSION_TAG: &str = "submission";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    /// Input in LeetCode's format, one argument per line
+    pub input: String,
+    /// Answer of LeetCode's reference solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl TestCase {
+    pub fn new(input: &str) -> Self {
+        Self {
+            input: normalize_input(input),
+            expected: None,
+            tags: Vec::new(),
+            enabled: true,
+        }
+    }
+}
+
+/// Testcases of a single question
+pub struct TestCaseStore {
+    path: PathBuf,
+    pub cases: Vec<TestCase>,
+}
+
+impl TestCaseStore {
+    /// Loads the testcases of `title_slug`, a missing store has no testcases
+    pub fn open(title_slug: &str) -> Result<Self> {
+        Self::load(Self::path(title_slug))
+    }
+
+    /// Loads the testcases saved at `path`
+    pub(crate) fn load(path: PathBuf) -> Result<Self> {
+        let cases = if path.exists() {
+            let contents = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&contents)
+                .wrap_err_with(|| format!("Failed to parse testcases in {}", path.display()))?
+        } else {
+            Vec::new()
+        };
+        Ok(Self { path, cases })
+    }
+
+    pub fn path(title_slug: &str) -> PathBuf {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let root = cwd
+            .ancestors()
+            .find(|dir| dir.join(STORE_DIR).is_dir())
+            .unwrap_or(&cwd);
+        root.join(STORE_DIR).join(title_slug).join(CASES_FILE)
+    }
+
+    pub fn location(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.cases)?)?;
+        Ok(())
+    }
+
+    /// Adds a testcase and returns its 1-based id, a testcase with the same
+    /// input gets its expected answer and tags instead of being duplicated.
+    /// It stays disabled if it was, re-adding examples or failed submissions
+    /// doesn't undo `testcase enable --disable`
+    pub fn add(&mut self, case: TestCase) -> usize {
+        match self.cases.iter().position(|c| c.input == case.input) {
+            Some(idx) => {
+                let existing = &mut self.cases[idx];
+                if case.expected.is_some() {
+                    existing.expected = case.expected;
+                }
+                for tag in case.tags {
+                    if !existing.tags.contains(&tag) {
+                        existing.tags.push(tag);
+                    }
+                }
+                idx + 1
+            }
+            None => {
+                self.cases.push(case);
+                self.cases.len()
+            }
+        }
+    }
+
+    /// Adds the question's example testcases, returns whether any was added
+    pub fn add_examples(&mut self, examples: &[String]) -> bool {
+        let before = self.cases.len();
+        for example in examples {
+            let mut case = TestCase::new(example);
+            case.tags.push(EXAMPLE_TAG.to_string());
+            self.add(case);
+        }
+        self.cases.len() > before
+    }
+
+    /// Adds the testcase a submission failed on with LeetCode's answer,
+    /// returns its 1-based id
+    pub fn add_failed_submission(&mut self, input: &str, expected: &str) -> usize {
+        let mut case = TestCase::new(input);
+        if !expected.is_empty() {
+            case.expected = Some(expected.to_string());
+        }
+        case.tags.push(SUBMISSION_TAG.to_string());
+        self.add(case)
+    }
+
+    pub fn enabled(&self) -> impl Iterator<Item = &TestCase> {
+        self.cases.iter().filter(|case| case.enabled)
+    }
+
+    /// Inputs of the enabled testcases, in the format expected by LeetCode
+    pub fn data_input(&self) -> String {
+        self.enabled()
+            .map(|case| case.input.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Saves LeetCode's answers for the enabled testcases, in the order
+    /// they were sent by [`TestCaseStore::data_input`]
+    pub fn record_expected(&mut self, answers: &[String]) {
+        for (case, answer) in self
+            .cases
+            .iter_mut()
+            .filter(|case| case.enabled)
+            .zip(answers)
+        {
+            case.expected = Some(answer.clone());
+        }
+    }
+
+    pub fn set_enabled(&mut self, ids: &[usize], enabled: bool) -> Result<()> {
+        for &id in ids {
+            self.get_mut(id)?.enabled = enabled;
+        }
+        Ok(())
+    }
+
+    /// Removes the testcases with the given 1-based ids, returns how many
+    /// were removed since the ids may repeat
+    pub fn remove(&mut self, ids: &[usize]) -> Result<usize> {
+        for &id in ids {
+            self.get_mut(id)?;
+        }
+        let before = self.cases.len();
+        let mut id = 0;
+        self.cases.retain(|_| {
+            id += 1;
+            !ids.contains(&id)
+        });
+        Ok(before - self.cases.len())
+    }
+
+    fn get_mut(&mut self, id: usize) -> Result<&mut TestCase> {
+        let total = self.cases.len();
+        match id.checked_sub(1).and_then(|idx| self.cases.get_mut(idx)) {
+            Some(case) => Ok(case),
+            None => bail!(
+                "No testcase with id {}, there are {} testcase(s)",
+                id,
+                total
+            ),
+        }
+    }
+}
+
+impl fmt::Display for TestCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:10}: {}",
+            "Input",
+            self.input.lines().collect::<Vec<_>>().join(", ")
+        )?;
+        writeln!(
+            f,
+            "{:10}: {}",
+            "Expected",
+            match &self.expected {
+                Some(expected) => expected.normal(),
+                None => "not captured yet".italic(),
+            }
+        )?;
+        if !self.tags.is_empty() {
+            writeln!(f, "{:10}: {}", "Tags", self.tags.join(", "))?;
+        }
+        write!(
+            f,
+            "{:10}: {}",
+            "Status",
+            if self.enabled {
+                "enabled".green()
+            } else {
+                "disabled".red()
+            }
+        )
+    }
+}
+
+/// Trims every line of the input and drops empty ones
+fn normalize_input(input: &str) -> String {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(dir: &tempfile::TempDir, inputs: &[&str]) -> TestCaseStore {
+        let mut store = TestCaseStore::load(dir.path().join("two-sum").join(CASES_FILE)).unwrap();
+        for input in inputs {
+            store.add(TestCase::new(input));
+        }
+        store
+    }
+
+    #[test]
+    fn saves_and_loads_testcases() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut saved = store(&dir, &["[2,7]\n9", "[3,3]\n6"]);
+        saved.cases[1].enabled = false;
+        saved.save().unwrap();
+
+        let loaded = store(&dir, &[]);
+        assert_eq!(loaded.cases.len(), 2);
+        assert_eq!(loaded.cases[0].input, "[2,7]\n9");
+        assert!(!loaded.cases[1].enabled);
+    }
+
+    #[test]
+    fn deduplicates_inputs_and_keeps_them_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(&dir, &["[2,7]\n9"]);
+        store.set_enabled(&[1], false).unwrap();
+
+        assert!(!store.add_examples(&[" [2,7] \n\n 9 ".to_string()]));
+        assert_eq!(store.add_failed_submission("[2,7]\n9", "[0,1]"), 1);
+        assert_eq!(store.cases.len(), 1);
+        let case = &store.cases[0];
+        assert!(!case.enabled);
+        assert_eq!(case.expected.as_deref(), Some("[0,1]"));
+        assert_eq!(case.tags, [EXAMPLE_TAG, SUBMISSION_TAG]);
+    }
+
+    #[test]
+    fn ids_are_one_based() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(&dir, &["1", "2", "3"]);
+        assert!(store.set_enabled(&[0], false).is_err());
+        assert!(store.set_enabled(&[4], false).is_err());
+        store.set_enabled(&[3], false).unwrap();
+        assert!(!store.cases[2].enabled);
+
+        assert!(store.remove(&[1, 4]).is_err());
+        assert_eq!(store.cases.len(), 3);
+        assert_eq!(store.remove(&[2, 2, 1]).unwrap(), 2);
+        let inputs: Vec<&str> = store.cases.iter().map(|case| case.input.as_str()).collect();
+        assert_eq!(inputs, ["3"]);
+    }
+
+    #[test]
+    fn records_answers_of_the_enabled_testcases() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(&dir, &["1", "2", "3"]);
+        store.set_enabled(&[2], false).unwrap();
+        assert_eq!(store.data_input(), "1\n3");
+
+        store.record_expected(&["one".to_string(), "three".to_string()]);
+        let expected: Vec<Option<&str>> = store
+            .cases
+            .iter()
+            .map(|case| case.expected.as_deref())
+            .collect();
+        assert_eq!(expected, [Some("one"), None, Some("three")]);
+    }
+}
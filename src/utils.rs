@@ -1,106 +1,316 @@
-use std::path::Path;
+use std::{
+    fmt,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
 
-use crate::file_parser::codefile::CodeFile;
-use crate::handlers::leetcode::{Authorized, LeetCode};
+use crate::args::{CacheCommands, ConfigCommands, ProfileCommands, TestcaseCommands};
+use crate::cache;
+use crate::config::{
+    render_template, validate_profile_name, Config, CookieSource, LOCAL_CONFIG_FILE,
+};
+use crate::cookies::CookieJar;
+use crate::credentials;
+
+use crate::file_parser::{codefile::CodeFile, language::Language, problem_ref::ProblemRef};
+use crate::handlers::helpers::LanguageChoice;
+use crate::handlers::leetcode::{Authorized, LeetCode, Unauthorized};
+use crate::handlers::problems::{
+    is_slug, matching_problems, ProblemFilter, ProblemList, ProblemStatus, ProblemSummary,
+};
+use crate::handlers::question::QuestionDetail;
 use crate::handlers::utils::{ExecutionResult, SubmissionResult};
+use crate::output::{self, AuthError, Verdict, VerdictError};
+use crate::runner::{self, LocalRun};
+use crate::testcases::{TestCase, TestCaseStore};
 use crate::GIT_README;
 
-use eyre::{bail, Result};
+use colored::Colorize;
+use eyre::{bail, Context, Result};
+use serde::Serialize;
+
+/// Code file at `file_path`, or the one found in the current directory
+pub(crate) fn load_code_file<P: AsRef<Path>>(file_path: Option<P>) -> Result<CodeFile> {
+    match file_path {
+        Some(path) => CodeFile::from_file(path),
+        None => CodeFile::from_dir("."),
+    }
+}
 
-/// The first element of the return tuple indicates whether the answer is correct.
-pub(crate) fn execute_testcases<P: AsRef<Path>>(
-    file_path: Option<P>,
+/// Runs the testcase file if given, otherwise the enabled testcases saved for the question.
+/// Returns whether the answer is correct.
+pub(crate) fn execute_testcases(
+    code_file: &CodeFile,
+    question: &QuestionDetail,
     testcases: Option<String>,
     lc: &LeetCode<Authorized>,
-) -> Result<(bool, CodeFile)> {
-    let code_file = if let Some(path) = file_path {
-        CodeFile::from_file(path)?
-    } else {
-        CodeFile::from_dir(".")?
+) -> Result<bool> {
+    let mut store = None;
+    let data_input = match testcases {
+        Some(testcases) => std::fs::read_to_string(testcases)?,
+        None => {
+            let saved = saved_testcases(&code_file.question_title, &question.exampleTestcaseList)?;
+            let data_input = saved.data_input();
+            store = Some(saved);
+            data_input
+        }
     };
+    let eval_res = lc.execute(code_file, question, data_input)?;
+    let verdict = eval_res.verdict();
+    if let (ExecutionResult::Success(result), Some(store)) = (&eval_res, store.as_mut()) {
+        store.record_expected(&result.expected_code_answer);
+        store.save()?;
+    }
+    output::result("run", Some(verdict), &eval_res);
+    match eval_res {
+        ExecutionResult::Success(result) => Ok(result.is_correct()),
+        _ => bail!(VerdictError(verdict)),
+    }
+}
+
+/// Runs the testcases with the local toolchain instead of LeetCode's judge.
+/// Saved testcases are compared with their captured answers, a testcase file
+/// with the answers in `expected_file`.
+/// Returns whether the answer is correct.
+pub(crate) fn execute_locally(
+    code_file: &CodeFile,
+    question: &QuestionDetail,
+    testcases: Option<String>,
+    expected_file: Option<PathBuf>,
+) -> Result<bool> {
+    if !runner::is_supported(code_file.language) {
+        bail!(
+            "{} is not supported by the local runner",
+            code_file.language
+        );
+    }
+    if code_file.language == Language::Bash {
+        return execute_script_locally(code_file, question, testcases, expected_file);
+    }
 
-    let eval_res = match testcases {
+    let (data_input, expected) = match testcases {
         Some(testcases) => {
-            let data_input = std::fs::read_to_string(testcases)?;
-            lc.execute(&code_file, data_input)
+            let expected = match expected_file {
+                Some(expected_file) => std::fs::read_to_string(expected_file)?
+                    .lines()
+                    .map(|line| Some(line.to_string()))
+                    .collect(),
+                None => Vec::new(),
+            };
+            (std::fs::read_to_string(testcases)?, expected)
         }
-        None => lc.execute_default(&code_file),
-    }?;
-    match eval_res {
-        ExecutionResult::Success(result) => {
-            println!("{}", result);
-            Ok((result.is_correct(), code_file))
+        None => {
+            let store = saved_testcases(&code_file.question_title, &question.exampleTestcaseList)?;
+            let expected = store
+                .enabled()
+                .map(|case| case.expected.clone())
+                .collect::<Vec<_>>();
+            if expected.iter().any(Option::is_none) {
+                output::info(
+                    "Some testcases have no expected answer yet, run them on LeetCode once to capture it",
+                );
+            }
+            (store.data_input(), expected)
         }
-        ExecutionResult::LimitExceeded(limit_exceeded) => bail!(limit_exceeded),
-        ExecutionResult::CompileError(compile_error) => bail!(compile_error),
-        ExecutionResult::RuntimeError(runtime_error) => bail!(runtime_error),
-        ExecutionResult::PendingResult(pending) => bail!(pending.state),
-        ExecutionResult::WrongTestcase(wrong_testcase) => bail!(wrong_testcase),
-        ExecutionResult::Unknown(_) => bail!("Unknown error occured"),
-    }
+    };
+
+    output::status("executing", "Executing testcases locally...");
+    let local_run = if code_file.language.is_database() {
+        runner::sql::run(code_file, &question.mysqlSchemas, &data_input, expected)?
+    } else {
+        runner::run(code_file, &question.metaData, &data_input, expected)?
+    };
+    report_local_run(&local_run)
 }
 
-pub(crate) fn submit(lc: &LeetCode<Authorized>, code_file: CodeFile) -> Result<()> {
-    match lc.submit(&code_file)? {
-        SubmissionResult::Success(success) => println!("{}", success),
-        SubmissionResult::LimitExceeded(wrong) => bail!(wrong),
-        SubmissionResult::PendingResult(state) => bail!(state.state),
-        SubmissionResult::CompileError(compile_err) => bail!(compile_err),
-        SubmissionResult::RuntimeError(runtime_error) => bail!(runtime_error),
-        SubmissionResult::Wrong(wrong) => bail!(wrong),
-        SubmissionResult::Unknown(_) => bail!("Unknown error occured"),
+/// Runs a shell script on the example file of the question, or on the
+/// testcase file which is then compared with the whole `expected_file`
+fn execute_script_locally(
+    code_file: &CodeFile,
+    question: &QuestionDetail,
+    testcases: Option<String>,
+    expected_file: Option<PathBuf>,
+) -> Result<bool> {
+    let content = question.content()?;
+    let (file, expected) = match testcases {
+        Some(testcases) => (
+            std::fs::read_to_string(testcases)?,
+            expected_file.map(std::fs::read_to_string).transpose()?,
+        ),
+        None => runner::bash::example(content)?,
     };
+    let file_name = runner::bash::input_file(content);
+    output::status(
+        "executing",
+        format!("Executing the script locally on {}...", file_name),
+    );
+    let local_run = runner::bash::run(code_file, &file_name, vec![file], vec![expected])?;
+    report_local_run(&local_run)
+}
 
-    Ok(())
+/// Prints a local run, returns whether the answer is correct
+fn report_local_run(local_run: &LocalRun) -> Result<bool> {
+    let verdict = local_run.verdict();
+    output::result("run", Some(verdict), local_run);
+    match verdict {
+        Verdict::CompileError | Verdict::RuntimeError => bail!(VerdictError(verdict)),
+        _ => Ok(local_run.is_correct()),
+    }
 }
 
-pub(crate) fn pack(lc: &LeetCode<Authorized>, file: Option<std::path::PathBuf>) -> Result<()> {
-    let code_file = if let Some(path) = file {
-        CodeFile::from_file(&path)?
+/// Saved testcases of a question, seeded with its examples the first time
+fn saved_testcases(title_slug: &str, examples: &[String]) -> Result<TestCaseStore> {
+    let mut store = TestCaseStore::open(title_slug)?;
+    if store.cases.is_empty() && store.add_examples(examples) {
+        store.save()?;
+        output::info(format!(
+            "Saved example testcases to {}",
+            store.location().display().to_string().cyan()
+        ));
+    }
+    if store.enabled().next().is_none() {
+        bail!(
+            "All testcases of {} are disabled, enable some with `testcase enable`",
+            title_slug
+        );
+    }
+    Ok(store)
+}
+
+pub(crate) fn submit(
+    lc: &LeetCode<Authorized>,
+    code_file: &CodeFile,
+    question: &QuestionDetail,
+) -> Result<()> {
+    let result = lc.submit(code_file, question)?;
+    let verdict = result.verdict();
+    output::result("submit", Some(verdict), &result);
+    if let SubmissionResult::Success(_) = result {
+        return Ok(());
+    }
+    if let Some((input, expected)) = failed_testcase(&result) {
+        save_failed_testcase(question, input, expected)?;
+    }
+    bail!(VerdictError(verdict))
+}
+
+/// Reports the result of an earlier run or submission, submissions have numeric ids
+pub(crate) fn check(lc: &LeetCode<Authorized>, id: &str) -> Result<()> {
+    let verdict = if id.chars().all(|c| c.is_ascii_digit()) {
+        let result = lc.check_submission(id)?;
+        output::result("submit", Some(result.verdict()), &result);
+        result.verdict()
     } else {
-        CodeFile::from_dir(".")?
+        let result = lc.check_run(id)?;
+        output::result("run", Some(result.verdict()), &result);
+        result.verdict()
     };
-    let question = lc.question_content(&code_file.question_title)?;
+    if verdict != Verdict::Accepted {
+        bail!(VerdictError(verdict));
+    }
+    Ok(())
+}
 
-    // create a directory if it doesn't exists with name of question
+/// Input and expected answer of the testcase a submission failed on
+fn failed_testcase(result: &SubmissionResult) -> Option<(&str, &str)> {
+    match result {
+        SubmissionResult::RuntimeError(runtime_error) => {
+            Some((&runtime_error.last_testcase, &runtime_error.expected_output))
+        }
+        SubmissionResult::Wrong(wrong) => Some((&wrong.last_testcase, &wrong.expected_output)),
+        _ => None,
+    }
+}
+
+/// Saves the testcase a submission failed on so the next run reproduces it
+fn save_failed_testcase(question: &QuestionDetail, input: &str, expected: &str) -> Result<()> {
+    let mut store = TestCaseStore::open(&question.titleSlug)?;
+    let Some(id) = add_failed_testcase(&mut store, &question.exampleTestcaseList, input, expected)
+    else {
+        return Ok(());
+    };
+    store.save()?;
+    output::info(format!(
+        "Saved the failing testcase as testcase {} in {}",
+        id,
+        store.location().display().to_string().cyan()
+    ));
+    Ok(())
+}
+
+/// Adds a failed testcase to `store`, returns its id unless the input is empty
+fn add_failed_testcase(
+    store: &mut TestCaseStore,
+    examples: &[String],
+    input: &str,
+    expected: &str,
+) -> Option<usize> {
+    if input.trim().is_empty() {
+        return None;
+    }
+    if store.cases.is_empty() {
+        // keep the examples which would otherwise be added by the first run
+        store.add_examples(examples);
+    }
+    Some(store.add_failed_submission(input, expected))
+}
+
+pub(crate) fn pack<State>(
+    lc: &LeetCode<State>,
+    file: Option<std::path::PathBuf>,
+    layout: &str,
+) -> Result<()> {
+    let code_file = load_code_file(file)?;
+    let question = lc.question_detail(&code_file.question_title)?;
+    let content = question.content()?;
+
+    // create the directory of the packed file if it doesn't exists
     // create a README.md file with the question description
     // create a file with the code
-    std::fs::create_dir_all(&code_file.question_title)?;
+    let code_path = PathBuf::from(render_template(
+        layout,
+        &code_file.question_title,
+        code_file.language,
+    ));
+    let pack_dir = match code_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    std::fs::create_dir_all(&pack_dir)?;
 
-    std::fs::write(
-        format!(
-            "{}/main.{}",
-            &code_file.question_title,
-            code_file.language.extension()
-        ),
-        code_file.code,
-    )?;
+    std::fs::write(&code_path, code_file.code)?;
 
     // dont create readme if it exists
+    let readme_path = pack_dir.join(GIT_README);
     if let Ok(mut readme_file) = std::fs::OpenOptions::new()
         .create_new(true)
         .write(true)
-        .open(format!("{}/{}", &code_file.question_title, GIT_README))
+        .open(&readme_path)
     {
         println!(
-            "You can write your notes about question in {}/README.md",
-            &code_file.question_title
+            "You can write your notes about question in {}",
+            readme_path.display()
         );
         std::io::Write::write_all(
             &mut readme_file,
             format!(
-                "# {title}\n[Link to Problem](https://leetcode.com/problems/{title}/description)\n\n",
-                title = code_file.question_title
+                "# {title}\n[Link to Problem]({link}description)\n\n",
+                title = code_file.question_title,
+                link = lc.problem_url(&code_file.question_title)
             )
             .as_bytes(),
         )?;
-        std::io::Write::write_all(&mut readme_file, question.content.as_bytes())?;
+        std::io::Write::write_all(&mut readme_file, content.as_bytes())?;
     } else {
-        println!("{} already exists, skipping creation.", GIT_README);
+        println!(
+            "{} already exists, skipping creation.",
+            readme_path.display()
+        );
     };
 
     let mut root_readme_file = std::fs::OpenOptions::new()
         .create(true)
+        .truncate(false)
         .read(true)
         .write(true)
         .open(GIT_README)?;
@@ -126,8 +336,749 @@ pub(crate) fn pack(lc: &LeetCode<Authorized>, file: Option<std::path::PathBuf>)
     if write_to_root {
         std::io::Write::write_all(
             &mut root_readme_file,
-            format!("- [{title}]({title}/)\n", title = code_file.question_title,).as_bytes(),
+            format!(
+                "- [{title}]({dir}/)\n",
+                title = code_file.question_title,
+                dir = pack_dir.display()
+            )
+            .as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Most candidates listed when a question name is ambiguous
+const MAX_CANDIDATES: usize = 10;
+
+/// Title slug of a question given by url, slug, frontend number or title,
+/// the numbers and titles are looked up in the problem list
+pub(crate) fn resolve_question<State>(
+    lc: &LeetCode<State>,
+    question: &ProblemRef,
+) -> Result<String> {
+    let name = match question {
+        ProblemRef::Url {
+            slug,
+            host,
+            contest,
+        } => return Ok(url_slug(lc, slug, host.as_deref(), contest.as_deref())),
+        ProblemRef::Number(name) | ProblemRef::Name(name) => name.as_str(),
+    };
+    let problems = problem_list_for(lc, &[name])?;
+    resolve_name(problems.as_deref(), name, true)
+}
+
+/// Slug of a question given by url, telling when it's fetched from elsewhere
+fn url_slug<State>(
+    lc: &LeetCode<State>,
+    slug: &str,
+    host: Option<&str>,
+    contest: Option<&str>,
+) -> String {
+    let base_host = lc.host();
+    if let Some(host) = host.filter(|host| *host != base_host) {
+        output::info(format!(
+            "{} is a question of {}, fetching it from {}, change it with --base-url",
+            slug, host, base_host
+        ));
+    }
+    if let Some(contest) = contest {
+        output::info(format!(
+            "{} is a question of the contest {}, fetching it from the problem set",
+            slug, contest
+        ));
+    }
+    slug.to_string()
+}
+
+/// Problem list to look `names` up in, `None` when there's nothing to look up
+/// or it can't be fetched but the names are slugs, enough to fetch the questions
+fn problem_list_for<State>(
+    lc: &LeetCode<State>,
+    names: &[&str],
+) -> Result<Option<Vec<ProblemSummary>>> {
+    if names.is_empty() {
+        return Ok(None);
+    }
+    match lc.problem_list(false) {
+        Ok(problems) => Ok(Some(problems)),
+        Err(_) if names.iter().all(|name| is_slug(name)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Title slug of the question a number, slug or title refers to. Without
+/// `choose`, or anyone to ask, a name matching several questions is an error
+fn resolve_name(problems: Option<&[ProblemSummary]>, name: &str, choose: bool) -> Result<String> {
+    let Some(problems) = problems else {
+        return Ok(name.to_string());
+    };
+    let candidates = matching_problems(problems, name);
+    let problem = match candidates[..] {
+        // a slug missing from the list is newer than it, never another question
+        [] if is_slug(name) => return Ok(name.to_string()),
+        [] => bail!("No question matches {}", name),
+        [problem] => problem,
+        _ if choose && std::io::stdin().is_terminal() && output::is_text() => {
+            choose_problem(&candidates)?
+        }
+        _ => bail!(
+            "{} matches several questions, pass one of them :\n{}",
+            name,
+            candidates
+                .iter()
+                .take(MAX_CANDIDATES)
+                .map(|problem| format!("{} ({})", problem.titleSlug, problem.title))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    };
+    if problem.titleSlug != name {
+        output::info(format!(
+            "Question {}. {}",
+            problem.frontendQuestionId,
+            problem.title.cyan()
+        ));
+    }
+    Ok(problem.titleSlug.clone())
+}
+
+fn choose_problem<'a>(candidates: &[&'a ProblemSummary]) -> Result<&'a ProblemSummary> {
+    let shown = &candidates[..candidates.len().min(MAX_CANDIDATES)];
+    output::info("\nSeveral questions match, please select one of them :".yellow());
+    for (i, problem) in shown.iter().enumerate() {
+        output::info(format!("{}: {}", i, problem));
+    }
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    let input = input.trim().parse::<usize>()?;
+    match shown.get(input) {
+        Some(problem) => Ok(problem),
+        None => bail!("Invalid option {}", input),
+    }
+}
+
+#[derive(Default, Serialize)]
+struct DownloadReport {
+    saved: Vec<SavedQuestion>,
+    failed: Vec<FailedDownload>,
+}
+
+#[derive(Serialize)]
+struct SavedQuestion {
+    title_slug: String,
+    html: PathBuf,
+    /// Missing when the code file already existed
+    code: Option<PathBuf>,
+    /// Tables of database questions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct FailedDownload {
+    title_slug: String,
+    error: String,
+}
+
+impl fmt::Display for DownloadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.saved.len() + self.failed.len();
+        let summary = format!("Downloaded {} of {} questions", self.saved.len(), total);
+        if self.failed.is_empty() {
+            return write!(f, "{}", summary.green().bold());
+        }
+        write!(f, "{}", summary.red().bold())?;
+        for failed in &self.failed {
+            write!(f, "\n{} : {}", failed.title_slug.cyan(), failed.error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Saves many questions as HTML with their boilerplate code,
+/// fetching `jobs` questions at once
+pub(crate) fn download<State>(
+    lc: &LeetCode<State>,
+    config: &Config,
+    mut questions: Vec<ProblemRef>,
+    from_file: Option<PathBuf>,
+    lang: Option<Language>,
+    output: Option<String>,
+    jobs: usize,
+) -> Result<()> {
+    if let Some(path) = from_file {
+        let list = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        for line in list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            questions.push(line.parse()?);
+        }
+    }
+    if questions.is_empty() {
+        bail!("No questions to download");
+    }
+    // there is nobody to ask in the middle of a bulk download
+    let Some(language) = lang.or(config.language) else {
+        bail!("Specify the language of the boilerplate code with --lang or `config set language`");
+    };
+    let layout = output.as_deref().unwrap_or(config.pack_layout());
+    if questions.len() > 1 && !layout.contains("{slug}") {
+        bail!(
+            "The output path {} must contain {{slug}}, otherwise the questions overwrite each other",
+            layout
+        );
+    }
+
+    let names: Vec<&str> = questions
+        .iter()
+        .filter_map(|question| match question {
+            ProblemRef::Number(name) | ProblemRef::Name(name) => Some(name.as_str()),
+            ProblemRef::Url { .. } => None,
+        })
+        .collect();
+    let problems = problem_list_for(lc, &names)?;
+    let mut report = DownloadReport::default();
+    let mut title_slugs: Vec<String> = Vec::with_capacity(questions.len());
+    for question in &questions {
+        let title_slug = match question {
+            ProblemRef::Url {
+                slug,
+                host,
+                contest,
+            } => Ok(url_slug(lc, slug, host.as_deref(), contest.as_deref())),
+            ProblemRef::Number(name) | ProblemRef::Name(name) => {
+                resolve_name(problems.as_deref(), name, false)
+            }
+        };
+        match title_slug {
+            Ok(title_slug) if !title_slugs.contains(&title_slug) => title_slugs.push(title_slug),
+            Ok(_) => {}
+            Err(err) => report.failed.push(FailedDownload {
+                title_slug: question.to_string(),
+                error: format!("{:#}", err),
+            }),
+        }
+    }
+
+    if !title_slugs.is_empty() {
+        output::info(format!("Fetching {} questions...", title_slugs.len()));
+        let alc = lc.to_async()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let fetched = runtime.block_on(alc.fetch_questions(&title_slugs, jobs));
+        for (title_slug, question) in fetched {
+            match question
+                .and_then(|question| save_question(lc, config, &question, language, layout))
+            {
+                Ok(saved) => report.saved.push(saved),
+                Err(err) => report.failed.push(FailedDownload {
+                    title_slug,
+                    error: format!("{:#}", err),
+                }),
+            }
+        }
+    }
+    // completion order is arbitrary, list them in the order asked for
+    let position = |title_slug: &str| title_slugs.iter().position(|slug| slug == title_slug);
+    report
+        .saved
+        .sort_by_key(|saved| position(&saved.title_slug));
+    report
+        .failed
+        .sort_by_key(|failed| position(&failed.title_slug));
+
+    output::result("download", None, &report);
+    if !report.failed.is_empty() {
+        bail!("Failed to download {} question(s)", report.failed.len());
+    }
+    Ok(())
+}
+
+/// Prints the problems matching `filter`, only their slugs with `slugs`
+pub(crate) fn list<State>(lc: &LeetCode<State>, filter: &ProblemFilter, slugs: bool) -> Result<()> {
+    let problems: Vec<ProblemSummary> = lc
+        .problem_list(filter.status.is_some())?
+        .into_iter()
+        .filter(|problem| filter.matches(problem))
+        .collect();
+    if slugs && output::is_text() {
+        for problem in &problems {
+            println!("{}", problem.titleSlug);
+        }
+    } else {
+        output::result("list", None, &ProblemList { problems });
+    }
+    Ok(())
+}
+
+/// Picks one of the problems matching `filter` at random, leaving out the
+/// ones already saved at the `layout` path. Unless a `seed` is given one is
+/// made up, and told so that the pick can be repeated
+pub(crate) fn random_problem<State>(
+    lc: &LeetCode<State>,
+    filter: &ProblemFilter,
+    unsolved: bool,
+    seed: Option<u64>,
+    layout: &str,
+) -> Result<ProblemSummary> {
+    let mut candidates: Vec<ProblemSummary> = lc
+        .problem_list(filter.status.is_some() || unsolved)?
+        .into_iter()
+        .filter(|problem| filter.matches(problem))
+        .filter(|problem| !unsolved || problem.status() != ProblemStatus::Solved)
+        .filter(|problem| !is_saved(layout, &problem.titleSlug))
+        .collect();
+    if candidates.is_empty() {
+        bail!("No problem matches the filters, except those already packed");
+    }
+
+    let seed = seed.unwrap_or_else(|| {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        now.as_secs() ^ u64::from(now.subsec_nanos())
+    });
+    let pick = splitmix64(seed) % candidates.len() as u64;
+    output::info(format!(
+        "Picked 1 of {} problems with seed {}",
+        candidates.len(),
+        seed
+    ));
+    Ok(candidates.swap_remove(pick as usize))
+}
+
+/// Whether a code file of the question exists at the `layout` path, in any language
+fn is_saved(layout: &str, title_slug: &str) -> bool {
+    Language::all()
+        .any(|language| Path::new(&render_template(layout, title_slug, language)).exists())
+}
+
+/// Spreads the bits of `seed`, consecutive seeds pick far apart problems
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Saves the question as HTML and opens it, along with its boilerplate code
+/// unless `no_code_save` is set
+pub(crate) fn open_question<State>(
+    lc: &LeetCode<State>,
+    config: &Config,
+    title_slug: &str,
+    no_code_save: bool,
+    lang: Option<Language>,
+    output: Option<String>,
+) -> Result<()> {
+    let question = lc.question_detail(title_slug)?;
+    if !no_code_save {
+        lc.save_boiler_code(
+            &question,
+            language_choice(lang, config),
+            output.as_deref().or(config.filename.as_deref()),
         )?;
     }
+
+    let filename = format!("{}.html", question.titleSlug);
+    let path = save_html(config, &filename, question.content()?)?;
+    open::that(path)?;
+    Ok(())
+}
+
+/// Saves a fetched question, existing code files are left untouched
+fn save_question<State>(
+    lc: &LeetCode<State>,
+    config: &Config,
+    question: &QuestionDetail,
+    language: Language,
+    layout: &str,
+) -> Result<SavedQuestion> {
+    let title_slug = &question.titleSlug;
+    let content = question.content()?;
+    let Some(snippet) = question.snippet(language) else {
+        bail!("No boiler plate code available in {}", language);
+    };
+
+    let code_path = PathBuf::from(render_template(layout, title_slug, language));
+    let code = if code_path.exists() {
+        None
+    } else {
+        if let Some(parent) = code_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        snippet.save_code(&code_path, &lc.problem_url(title_slug))?;
+        Some(code_path.clone())
+    };
+    let schema = if language.is_database() {
+        question.save_schema(&code_path)?
+    } else {
+        None
+    };
+    let html = save_html(config, &format!("{}.html", title_slug), content)?;
+
+    let mut store = TestCaseStore::open(title_slug)?;
+    if store.cases.is_empty() && store.add_examples(&question.exampleTestcaseList) {
+        store.save()?;
+    }
+
+    Ok(SavedQuestion {
+        title_slug: title_slug.clone(),
+        html,
+        code,
+        schema,
+    })
+}
+
+/// Language given on the command line is required, the configured one is only preferred
+pub(crate) fn language_choice(lang: Option<Language>, config: &Config) -> LanguageChoice {
+    match (lang, config.language) {
+        (Some(language), _) => LanguageChoice::Required(language),
+        (None, Some(language)) => LanguageChoice::Preferred(language),
+        (None, None) => LanguageChoice::Ask,
+    }
+}
+
+/// Saves question content as HTML inside the configured directory
+pub(crate) fn save_html(config: &Config, filename: &str, content: &str) -> Result<PathBuf> {
+    let html_dir = config.html_dir();
+    std::fs::create_dir_all(html_dir)?;
+    let path = html_dir.join(filename);
+    std::fs::write(&path, content)?;
+    output::info(format!(
+        "Saved question as HTML to {}",
+        path.display().to_string().cyan()
+    ));
+    Ok(path)
+}
+
+pub(crate) fn configure(command: ConfigCommands, config: &Config) -> Result<()> {
+    match command {
+        ConfigCommands::Get { key } => match config.get(&key)? {
+            Some(value) => println!("{}", value),
+            None => bail!("{} is not set", key),
+        },
+        ConfigCommands::Set { key, value, local } => {
+            let path = if local {
+                Config::local_path().unwrap_or_else(|| PathBuf::from(LOCAL_CONFIG_FILE))
+            } else {
+                Config::global_path()
+                    .ok_or_else(|| eyre::eyre!("Could not locate the config directory"))?
+            };
+            let mut file_config = Config::from_file(&path)?;
+            file_config.set(&key, &value)?;
+            file_config.save(&path)?;
+            println!("Updated {}", path.display().to_string().cyan());
+        }
+        ConfigCommands::List => {
+            for path in [Config::global_path(), Config::local_path()]
+                .into_iter()
+                .flatten()
+                .filter(|path| path.is_file())
+            {
+                println!("# {}", path.display());
+            }
+            print!("{}", toml::to_string_pretty(config)?);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct Login {
+    pub user_name: String,
+    pub path: PathBuf,
+    pub encrypted: bool,
+}
+
+impl fmt::Display for Login {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Logged in as {}, the cookie is saved{} in {}",
+            self.user_name.green(),
+            if self.encrypted { " encrypted" } else { "" },
+            self.path.display().to_string().cyan()
+        )
+    }
+}
+
+/// Checks the cookie of `source`, or the one given on stdin, and saves it
+pub(crate) fn login(
+    lc: &LeetCode<Unauthorized>,
+    source: Option<CookieSource>,
+    encrypt: bool,
+) -> Result<()> {
+    let cookie = match source {
+        Some(source) => source.read(&lc.host())?,
+        None => read_cookie_input()?,
+    };
+    let jar = CookieJar::parse(&cookie, &lc.host())?;
+    let metadata = lc.authenticate(&jar)?.get_metadata()?;
+    let path = credentials::save(&jar.header(), encrypt)?;
+    output::result(
+        "login",
+        None,
+        &Login {
+            user_name: metadata.user_name,
+            path,
+            encrypted: encrypt,
+        },
+    );
+    Ok(())
+}
+
+/// Cookie pasted on the terminal without echoing it, or a cookie file piped to stdin
+fn read_cookie_input() -> Result<String> {
+    let input = if std::io::stdin().is_terminal() {
+        rpassword::prompt_password("Cookie header of a logged in LeetCode tab: ")?
+    } else {
+        std::io::read_to_string(std::io::stdin())?
+    };
+    let cookie = input.trim();
+    if cookie.is_empty() {
+        bail!(AuthError("No cookie given".to_string()));
+    }
+    Ok(cookie.to_string())
+}
+
+pub(crate) fn logout() -> Result<()> {
+    if credentials::remove()? {
+        output::info("Logged out, the saved cookie is removed");
+    } else {
+        output::info("Not logged in");
+    }
+    Ok(())
+}
+
+/// `selected` is the profile given with `--profile`, if any
+pub(crate) fn manage_profiles(
+    command: ProfileCommands,
+    config: &Config,
+    selected: Option<&str>,
+) -> Result<()> {
+    let path = Config::global_path()
+        .ok_or_else(|| eyre::eyre!("Could not locate the config directory"))?;
+    let mut global = Config::from_file(&path)?;
+    match command {
+        ProfileCommands::Add {
+            name,
+            cookie_source,
+            lang,
+            workspace,
+        } => {
+            validate_profile_name(&name)?;
+            let workspace = workspace.map(std::path::absolute).transpose()?;
+            let added = !global.profiles.contains_key(&name);
+            let profile = global.profiles.entry(name.clone()).or_default();
+            profile.cookie_source = cookie_source.or(profile.cookie_source.take());
+            profile.language = lang.or(profile.language);
+            profile.workspace = workspace.or(profile.workspace.take());
+            global.save(&path)?;
+            println!(
+                "{} profile {}",
+                if added { "Added" } else { "Updated" },
+                name.cyan()
+            );
+        }
+        ProfileCommands::List => {
+            if config.profiles.is_empty() {
+                println!("No profiles, add one with `profile add <NAME>`");
+            }
+            for (name, profile) in &config.profiles {
+                let active = selected.or(config.profile.as_deref()) == Some(name.as_str());
+                println!(
+                    "{} {}",
+                    if active { "*" } else { " " },
+                    if active {
+                        name.green().bold()
+                    } else {
+                        name.normal()
+                    }
+                );
+                let cookie_source = profile
+                    .cookie_source
+                    .as_ref()
+                    .map_or("stored".to_string(), ToString::to_string);
+                println!("    {:14}: {}", "cookie source", cookie_source);
+                if let Some(language) = profile.language {
+                    println!("    {:14}: {}", "language", language);
+                }
+                if let Some(workspace) = &profile.workspace {
+                    println!("    {:14}: {}", "workspace", workspace.display());
+                }
+            }
+        }
+        ProfileCommands::Remove { name } => {
+            if global.profiles.remove(&name).is_none() {
+                bail!("No profile named {}", name);
+            }
+            if global.profile.as_deref() == Some(name.as_str()) {
+                global.profile = None;
+            }
+            global.save(&path)?;
+            credentials::remove_profile(&name)?;
+            println!("Removed profile {}", name.cyan());
+        }
+        ProfileCommands::Use { name } => {
+            if let Some(name) = &name {
+                if !global.profiles.contains_key(name) {
+                    bail!(
+                        "No profile named {}, add it with `profile add {}`",
+                        name,
+                        name
+                    );
+                }
+            }
+            global.profile = name;
+            global.save(&path)?;
+            match &global.profile {
+                Some(name) => println!("Using profile {}", name.cyan()),
+                None => println!("Using no profile"),
+            }
+        }
+    }
     Ok(())
 }
+
+pub(crate) fn manage_cache(command: CacheCommands, config: &Config) -> Result<()> {
+    match command {
+        CacheCommands::Clear { expired } => {
+            let ttl = expired.then(|| config.cache_ttl());
+            let removed = cache::clear(ttl)?;
+            output::info(format!("Removed {} cached entries", removed));
+        }
+        CacheCommands::Stats => {
+            let stats = cache::stats(config.cache_ttl())?;
+            output::result("cache", None, &stats);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn manage_testcases(
+    command: TestcaseCommands,
+    question: Option<String>,
+    file: Option<PathBuf>,
+) -> Result<()> {
+    let title_slug = match (question, file) {
+        (Some(question), _) => question,
+        (None, Some(path)) => CodeFile::from_file(path)?.question_title,
+        (None, None) => CodeFile::from_dir(".")?.question_title,
+    };
+    let mut store = TestCaseStore::open(&title_slug)?;
+    match command {
+        TestcaseCommands::Add {
+            input,
+            expected,
+            tag,
+            disabled,
+        } => {
+            let input = if input.is_empty() {
+                let mut input = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)?;
+                input
+            } else {
+                input.join("\n")
+            };
+            let mut case = TestCase::new(&input);
+            if case.input.is_empty() {
+                bail!("Testcase input can't be empty");
+            }
+            case.expected = expected;
+            case.tags = tag;
+            case.enabled = !disabled;
+            let id = store.add(case);
+            store.save()?;
+            println!("Saved testcase {} of {}", id, title_slug);
+        }
+        TestcaseCommands::List { tag } => {
+            let seperator = "-------------------------------".yellow();
+            let mut listed = 0;
+            for (idx, case) in store.cases.iter().enumerate() {
+                if tag.as_ref().is_some_and(|tag| !case.tags.contains(tag)) {
+                    continue;
+                }
+                println!(
+                    "{seperator}\n{}\n{seperator}",
+                    format!("Testcase {}", idx + 1).cyan()
+                );
+                println!("{}", case);
+                listed += 1;
+            }
+            if listed == 0 {
+                println!("No testcases saved for {}", title_slug);
+            }
+        }
+        TestcaseCommands::Remove { ids } => {
+            let removed = store.remove(&ids)?;
+            store.save()?;
+            println!("Removed {} testcase(s) of {}", removed, title_slug);
+        }
+        TestcaseCommands::Enable { ids, disable } => {
+            store.set_enabled(&ids, !disable)?;
+            store.save()?;
+            println!(
+                "{} {} testcase(s) of {}",
+                if disable { "Disabled" } else { "Enabled" },
+                ids.len(),
+                title_slug
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(state: &str, extra: serde_json::Value) -> SubmissionResult {
+        let mut result = serde_json::json!({
+            "status_code": 11, "lang": "rust", "run_success": true, "status_runtime": "N/A",
+            "memory": 0, "question_id": "1", "elapsed_time": 0, "compare_result": "10",
+            "code_output": "[1,0]", "std_output": "", "last_testcase": "[3,2,4]\n6",
+            "expected_output": "[1,2]", "task_finish_time": 0, "total_correct": 1,
+            "total_testcases": 60, "submission_id": "5", "status_msg": state, "state": "SUCCESS",
+        });
+        result
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(result).unwrap()
+    }
+
+    #[test]
+    fn saves_failed_testcases_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = TestCaseStore::load(dir.path().join("cases.json")).unwrap();
+        let examples = ["[2,7,11,15]\n9".to_string()];
+        let wrong = submission("Wrong Answer", serde_json::json!({"input": "[3,2,4]\n6"}));
+        let runtime_error = submission(
+            "Runtime Error",
+            serde_json::json!({"runtime_error": "panicked", "full_runtime_error": "panicked"}),
+        );
+        assert!(matches!(wrong, SubmissionResult::Wrong(_)));
+        assert!(matches!(runtime_error, SubmissionResult::RuntimeError(_)));
+
+        for result in [&wrong, &runtime_error] {
+            let (input, expected) = failed_testcase(result).unwrap();
+            assert_eq!(
+                add_failed_testcase(&mut store, &examples, input, expected),
+                Some(2)
+            );
+        }
+        assert_eq!(store.cases.len(), 2);
+        assert_eq!(store.cases[0].input, examples[0]);
+        assert_eq!(store.cases[1].input, "[3,2,4]\n6");
+        assert_eq!(store.cases[1].expected.as_deref(), Some("[1,2]"));
+
+        assert_eq!(add_failed_testcase(&mut store, &examples, " \n", ""), None);
+        assert_eq!(store.cases.len(), 2);
+    }
+}